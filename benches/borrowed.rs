@@ -0,0 +1,39 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use hocr_parser::{ElementBorrowed, HOCRBorrowed, HOCRView};
+
+const PATH: &str = "examples/data/paper-image.hocr";
+
+fn sum_borrowed(e: &ElementBorrowed) -> usize {
+    let values: usize = e.properties.iter().map(|(_, values)| values.len()).sum();
+    values + e.children.iter().map(sum_borrowed).sum::<usize>()
+}
+
+fn borrowed(c: &mut Criterion) {
+    let xml = std::fs::read_to_string(PATH).unwrap();
+    let options = roxmltree::ParsingOptions {
+        allow_dtd: true,
+        ..Default::default()
+    };
+    let doc = roxmltree::Document::parse_with_options(&xml, options).unwrap();
+
+    let mut group = c.benchmark_group("paper-image");
+    group.bench_function("ElementBorrowed", |b| {
+        b.iter(|| {
+            let hocr = HOCRBorrowed::new_from_document(black_box(&doc)).unwrap();
+            hocr.elements.iter().map(sum_borrowed).sum::<usize>()
+        })
+    });
+    group.bench_function("ElementView", |b| {
+        b.iter(|| {
+            let view = HOCRView::new(black_box(&doc)).unwrap();
+            view.iter()
+                .flat_map(|e| e.properties())
+                .map(|(_, values)| values.count())
+                .sum::<usize>()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, borrowed);
+criterion_main!(benches);
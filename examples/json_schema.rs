@@ -0,0 +1,4 @@
+fn main() {
+    let schema = hocr_parser::json::schema();
+    println!("{}", serde_json::to_string_pretty(&schema).unwrap());
+}
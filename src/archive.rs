@@ -0,0 +1,182 @@
+//! Entries whose names end in `.hocr`, `.html`, `.htm` or `.xhtml`, optionally followed by
+//! `.gz` or `.zst`, are parsed with [`HOCR::from_bytes`]; all other entries are skipped.
+//!
+//! ```no_run
+//! # #[cfg(feature = "zip")] {
+//! use hocr_parser::archive::{zip, zip_entries};
+//!
+//! let file = std::fs::File::open("book.zip").unwrap();
+//! let mut archive = zip::ZipArchive::new(file).unwrap();
+//! for (name, hocr) in zip_entries(&mut archive) {
+//!     match hocr {
+//!         Ok(hocr) => println!("{name}: {} pages", hocr.pages().count()),
+//!         Err(e) => eprintln!("{name}: {e}"),
+//!     }
+//! }
+//! # }
+//! ```
+
+use std::io::Read;
+#[cfg(feature = "zip")]
+use std::io::Seek;
+
+use crate::{compression::is_hocr_file_name, error::Result, hocr::HOCR};
+
+#[cfg(feature = "tar")]
+pub use tar;
+#[cfg(feature = "zip")]
+pub use zip;
+
+/// Whether an archive entry name looks like a (possibly compressed) hOCR file.
+pub fn is_hocr_entry(name: &str) -> bool {
+    is_hocr_file_name(name)
+}
+
+fn read_entry(mut entry: impl Read) -> Result<HOCR> {
+    let mut bytes = vec![];
+    entry.read_to_end(&mut bytes)?;
+    HOCR::from_bytes(&bytes)
+}
+
+/// Iterator over the hOCR entries of a zip archive, created by [`zip_entries`].
+#[cfg(feature = "zip")]
+pub struct ZipEntries<'a, R> {
+    archive: &'a mut zip::ZipArchive<R>,
+    index: usize,
+}
+
+/// Returns an iterator over the hOCR entries of a zip archive with their names.
+#[cfg(feature = "zip")]
+pub fn zip_entries<R: Read + Seek>(archive: &mut zip::ZipArchive<R>) -> ZipEntries<'_, R> {
+    ZipEntries { archive, index: 0 }
+}
+
+#[cfg(feature = "zip")]
+impl<R: Read + Seek> Iterator for ZipEntries<'_, R> {
+    type Item = (String, Result<HOCR>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < self.archive.len() {
+            let index = self.index;
+            self.index += 1;
+
+            let name = self.archive.name_for_index(index)?.to_string();
+            if name.ends_with('/') || !is_hocr_entry(&name) {
+                continue;
+            }
+
+            let hocr = self
+                .archive
+                .by_index(index)
+                .map_err(|e| crate::HOCRParserError::ArchiveError(e.to_string()))
+                .and_then(read_entry);
+            return Some((name, hocr));
+        }
+
+        None
+    }
+}
+
+/// Iterator over the hOCR entries of a tar archive, created by [`tar_entries`].
+#[cfg(feature = "tar")]
+pub struct TarEntries<'a, R: 'a + Read> {
+    entries: tar::Entries<'a, R>,
+}
+
+/// Returns an iterator over the hOCR entries of a tar archive with their names.
+///
+/// Entries are read in order, so the archive can come from a non-seekable stream, e.g. a
+/// [`flate2::read::GzDecoder`](https://docs.rs/flate2) for `.tar.gz` files.
+#[cfg(feature = "tar")]
+pub fn tar_entries<R: Read>(archive: &mut tar::Archive<R>) -> Result<TarEntries<'_, R>> {
+    Ok(TarEntries {
+        entries: archive.entries()?,
+    })
+}
+
+#[cfg(feature = "tar")]
+impl<R: Read> Iterator for TarEntries<'_, R> {
+    type Item = (String, Result<HOCR>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for entry in self.entries.by_ref() {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => return Some((String::new(), Err(e.into()))),
+            };
+
+            let name = String::from_utf8_lossy(&entry.path_bytes()).into_owned();
+            if !entry.header().entry_type().is_file() || !is_hocr_entry(&name) {
+                continue;
+            }
+
+            return Some((name, read_entry(entry)));
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hocr_entry_names() {
+        assert!(is_hocr_entry("book/0001.hocr"));
+        assert!(is_hocr_entry("book/0001.HTML.gz"));
+        assert!(is_hocr_entry("0001.xhtml.zst"));
+        assert!(!is_hocr_entry("book/0001.png"));
+        assert!(!is_hocr_entry("book/hocr"));
+    }
+
+    #[cfg(feature = "zip")]
+    #[test]
+    fn read_zip_entries() {
+        use std::io::{Cursor, Write};
+
+        let xml = std::fs::read("examples/data/paper-image.hocr").unwrap();
+        let mut writer = zip::ZipWriter::new(Cursor::new(vec![]));
+        let options = zip::write::SimpleFileOptions::default();
+        for name in ["book/", "book/0001.hocr", "book/0001.png", "book/0002.hocr"] {
+            if name.ends_with('/') {
+                writer.add_directory(name, options).unwrap();
+                continue;
+            }
+            writer.start_file(name, options).unwrap();
+            match name {
+                "book/0002.hocr" => writer.write_all(b"<html/>").unwrap(),
+                _ => writer.write_all(&xml).unwrap(),
+            }
+        }
+        let bytes = writer.finish().unwrap().into_inner();
+
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+        let entries: Vec<_> = zip_entries(&mut archive).collect();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].0, "book/0001.hocr");
+        assert_eq!(entries[0].1.as_ref().unwrap().pages().count(), 1);
+        assert!(entries[1].1.is_err());
+    }
+
+    #[cfg(feature = "tar")]
+    #[test]
+    fn read_tar_entries() {
+        let xml = std::fs::read("examples/data/paper-image.hocr").unwrap();
+        let mut builder = tar::Builder::new(vec![]);
+        for name in ["0001.hocr", "0001.txt"] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(xml.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, name, &xml[..]).unwrap();
+        }
+        let bytes = builder.into_inner().unwrap();
+
+        let mut archive = tar::Archive::new(&bytes[..]);
+        let entries: Vec<_> = tar_entries(&mut archive).unwrap().collect();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].0, "0001.hocr");
+        assert!(entries[0].1.is_ok());
+    }
+}
@@ -0,0 +1,178 @@
+use crate::{
+    element::{Element, ElementBorrowed},
+    geometry::{BBox, Transform},
+    traits::HocrElement,
+};
+
+/// The baseline of a line of text, as given by the `baseline` property.
+///
+/// `slope` and `offset` are relative to the bottom-left corner of the line's `bbox`: the baseline
+/// passes through `(x0, y1 + offset)` and rises by `slope` pixels per pixel to the right, with
+/// the y axis pointing down. The methods taking a `bbox` work in absolute page coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Baseline {
+    pub slope: f64,
+    pub offset: f64,
+}
+
+impl Baseline {
+    /// Create a new [`Baseline`] from its slope and offset.
+    pub fn new(slope: f64, offset: f64) -> Self {
+        Self { slope, offset }
+    }
+
+    /// Parse a [`Baseline`] from exactly two property values.
+    pub fn from_values<S: AsRef<str>>(values: &[S]) -> Option<Self> {
+        match values {
+            [slope, offset] => Some(Self::new(
+                slope.as_ref().parse().ok()?,
+                offset.as_ref().parse().ok()?,
+            )),
+            _ => None,
+        }
+    }
+
+    /// The y coordinate of the baseline at `x` on the page, for a line with box `bbox`.
+    pub fn y_at(&self, bbox: &BBox, x: f64) -> f64 {
+        bbox.y1 as f64 + self.offset + self.slope * (x - bbox.x0 as f64)
+    }
+
+    /// The baseline as a polyline from the left to the right edge of `bbox`.
+    pub fn polyline(&self, bbox: &BBox) -> [(f64, f64); 2] {
+        [bbox.x0, bbox.x1].map(|x| (x as f64, self.y_at(bbox, x as f64)))
+    }
+
+    /// The angle of the baseline in degrees, clockwise from horizontal on the page.
+    pub fn angle(&self) -> f64 {
+        self.slope.atan().to_degrees()
+    }
+
+    /// The baseline through two points on the page, relative to `bbox`, or `None` if the points
+    /// are vertically aligned.
+    pub fn through(bbox: &BBox, (x0, y0): (f64, f64), (x1, y1): (f64, f64)) -> Option<Self> {
+        // tolerate rounding errors, e.g. from a rotation by 90 degrees
+        if (x1 - x0).abs() < 1e-6 {
+            return None;
+        }
+
+        let slope = (y1 - y0) / (x1 - x0);
+        let offset = y0 + slope * (bbox.x0 as f64 - x0) - bbox.y1 as f64;
+        Some(Self::new(slope, offset))
+    }
+
+    /// Fit a baseline for a line with box `bbox` through the bottom centers of its word boxes by
+    /// least squares. Returns `None` if there are no words.
+    pub fn fit(bbox: &BBox, words: &[BBox]) -> Option<Self> {
+        if words.is_empty() {
+            return None;
+        }
+
+        let n = words.len() as f64;
+        let points: Vec<(f64, f64)> = words
+            .iter()
+            .map(|w| ((w.x0 + w.x1) as f64 / 2.0, w.y1 as f64))
+            .collect();
+        let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+        let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+        let variance: f64 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+        let covariance: f64 = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+
+        let slope = if variance > 0.0 {
+            covariance / variance
+        } else {
+            0.0
+        };
+        let offset = mean_y + slope * (bbox.x0 as f64 - mean_x) - bbox.y1 as f64;
+        Some(Self::new(slope, offset))
+    }
+
+    /// The baseline rounded as Tesseract writes it: the slope to three decimals and the offset
+    /// to whole pixels.
+    pub fn rounded(&self) -> Self {
+        // adding zero turns -0 into 0, which would otherwise be written as "-0"
+        Self::new(
+            (self.slope * 1000.0).round() / 1000.0 + 0.0,
+            self.offset.round() + 0.0,
+        )
+    }
+
+    /// Map the baseline of a line with box `bbox` by `t`, relative to the mapped box
+    /// `t.apply_bbox(bbox)`. Returns `None` if the mapped baseline is vertical.
+    pub fn transform(&self, bbox: &BBox, t: &Transform) -> Option<Self> {
+        let [start, end] = self.polyline(bbox).map(|(x, y)| t.apply(x, y));
+        Self::through(&t.apply_bbox(bbox), start, end)
+    }
+}
+
+impl std::fmt::Display for Baseline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.slope, self.offset)
+    }
+}
+
+impl<'a> ElementBorrowed<'a> {
+    /// Returns the parsed `baseline` property, if present and well-formed.
+    pub fn baseline(&self) -> Option<Baseline> {
+        HocrElement::baseline(self)
+    }
+}
+
+impl Element {
+    /// Returns the parsed `baseline` property, if present and well-formed.
+    pub fn baseline(&self) -> Option<Baseline> {
+        HocrElement::baseline(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evaluate_in_page_coordinates() {
+        let bbox = BBox::new(100, 50, 300, 80);
+        let baseline = Baseline::new(0.01, -5.0);
+
+        assert_eq!(baseline.y_at(&bbox, 100.0), 75.0);
+        assert_eq!(baseline.polyline(&bbox), [(100.0, 75.0), (300.0, 77.0)]);
+        assert!((baseline.angle() - 0.5729).abs() < 1e-3);
+        assert_eq!(
+            Baseline::through(&bbox, (100.0, 75.0), (300.0, 77.0)),
+            Some(baseline)
+        );
+    }
+
+    #[test]
+    fn fit_word_boxes() {
+        let line = BBox::new(10, 10, 170, 34);
+        let words = [
+            BBox::new(10, 10, 50, 30),
+            BBox::new(70, 12, 110, 32),
+            BBox::new(130, 14, 170, 34),
+        ];
+        let baseline = Baseline::fit(&line, &words).unwrap();
+        assert_eq!(baseline.rounded(), Baseline::new(0.033, -5.0));
+        assert!((baseline.y_at(&line, 90.0) - 32.0).abs() < 1e-9);
+        assert_eq!(Baseline::fit(&line, &[]), None);
+    }
+
+    #[test]
+    fn transform_baseline() {
+        let bbox = BBox::new(100, 50, 300, 80);
+        let baseline = Baseline::new(0.0, -5.0);
+
+        let shift = Transform::translate(10.0, 20.0);
+        assert_eq!(baseline.transform(&bbox, &shift), Some(baseline));
+
+        let rotate = Transform::rotate_about(180.0, 200.0, 65.0);
+        assert_eq!(rotate.apply_bbox(&bbox), bbox);
+        let rotated = baseline.transform(&bbox, &rotate).unwrap();
+        assert!(rotated.slope.abs() < 1e-9);
+        assert!((rotated.offset + 25.0).abs() < 1e-9);
+
+        let back = rotate.inverse().unwrap();
+        let (x, y) = rotate.then(&back).apply(120.0, 70.0);
+        assert!((x - 120.0).abs() < 1e-9 && (y - 70.0).abs() < 1e-9);
+        assert_eq!(baseline.transform(&bbox, &Transform::rotate(90.0)), None);
+    }
+}
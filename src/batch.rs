@@ -0,0 +1,280 @@
+//! Files are collected with [`find_files`] or [`glob_files`], then parsed and handed to a
+//! closure by [`process`], or converted to other formats by [`convert_dir`]. With the `rayon`
+//! feature, files are processed in parallel. Failures are collected in a [`BatchReport`]
+//! instead of aborting the batch.
+//!
+//! [`find_files`]: crate::batch::find_files
+//! [`glob_files`]: crate::batch::glob_files
+//! [`process`]: crate::batch::process
+//! [`convert_dir`]: crate::batch::convert_dir
+//! [`BatchReport`]: crate::batch::BatchReport
+//!
+//! ```no_run
+//! use hocr_parser::batch;
+//!
+//! let files = batch::find_files("corpus").unwrap();
+//! let report = batch::process(&files, |_, hocr| Ok(hocr.summary().words));
+//! let words: usize = report.succeeded.iter().map(|(_, words)| words).sum();
+//! println!("{words} words, {} files failed", report.failed.len());
+//! ```
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::{
+    compression::{is_hocr_file_name, strip_compressed_extension},
+    error::Result,
+    hocr::HOCR,
+    HOCRParserError,
+};
+
+/// Per-file results of a batch.
+#[derive(Debug, Clone)]
+pub struct BatchReport<T> {
+    /// Files that were processed successfully, with their results, in input order.
+    pub succeeded: Vec<(PathBuf, T)>,
+    /// Files that could not be read, parsed or processed, in input order.
+    pub failed: Vec<(PathBuf, HOCRParserError)>,
+}
+
+impl<T> BatchReport<T> {
+    /// Whether all files were processed successfully.
+    pub fn is_success(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    /// Total number of files in the batch.
+    pub fn len(&self) -> usize {
+        self.succeeded.len() + self.failed.len()
+    }
+
+    /// Whether the batch contained no files.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Built-in conversions for [`convert_dir`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Converter {
+    /// Plain text, see [`HOCR::plain_text`].
+    Text,
+    /// hOCR XHTML, see [`HOCR::to_xhtml`].
+    Xhtml,
+}
+
+impl Converter {
+    /// File extension of the output, without the leading dot.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Text => "txt",
+            Self::Xhtml => "hocr",
+        }
+    }
+
+    /// Convert a document.
+    pub fn convert(self, hocr: &HOCR) -> String {
+        match self {
+            Self::Text => hocr.plain_text(),
+            Self::Xhtml => hocr.to_xhtml(),
+        }
+    }
+}
+
+fn walk(dir: &Path, visited: &mut HashSet<PathBuf>, files: &mut Vec<PathBuf>) -> Result<()> {
+    // symlinks are followed, but each directory is only read once so that loops terminate
+    if !visited.insert(fs::canonicalize(dir)?) {
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            walk(&path, visited, files)?;
+        } else if path
+            .file_name()
+            .is_some_and(|name| is_hocr_file_name(&name.to_string_lossy()))
+        {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Recursively find hOCR files in `dir`, in sorted order.
+///
+/// Files ending in `.hocr`, `.html`, `.htm` or `.xhtml`, optionally followed by `.gz` or
+/// `.zst`, are included. Symbolic links are followed; a directory reached more than once, e.g.
+/// through a link to one of its ancestors, is only searched the first time.
+pub fn find_files<P: AsRef<Path>>(dir: P) -> Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    walk(dir.as_ref(), &mut HashSet::new(), &mut files)?;
+    files.sort();
+    Ok(files)
+}
+
+/// Find files matching a glob pattern such as `corpus/**/*.hocr`, in sorted order.
+pub fn glob_files(pattern: &str) -> Result<Vec<PathBuf>> {
+    let paths = glob::glob(pattern).map_err(|e| HOCRParserError::InvalidGlob(e.to_string()))?;
+    let mut files = vec![];
+    for path in paths {
+        let path = path.map_err(std::io::Error::from)?;
+        if path.is_file() {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Parse each file with [`HOCR::from_path`] and apply `f` to it.
+///
+/// Errors from reading, parsing or `f` are collected per file in the returned report.
+pub fn process<P, T, F>(paths: &[P], f: F) -> BatchReport<T>
+where
+    P: AsRef<Path> + Sync,
+    T: Send,
+    F: Fn(&Path, HOCR) -> Result<T> + Sync + Send,
+{
+    let run = |path: &P| {
+        let path = path.as_ref();
+        let result = HOCR::from_path(path).and_then(|hocr| f(path, hocr));
+        (path.to_path_buf(), result)
+    };
+
+    #[cfg(feature = "rayon")]
+    let results: Vec<_> = paths.par_iter().map(run).collect();
+    #[cfg(not(feature = "rayon"))]
+    let results: Vec<_> = paths.iter().map(run).collect();
+
+    let mut report = BatchReport {
+        succeeded: vec![],
+        failed: vec![],
+    };
+    for (path, result) in results {
+        match result {
+            Ok(value) => report.succeeded.push((path, value)),
+            Err(e) => report.failed.push((path, e)),
+        }
+    }
+    report
+}
+
+/// Convert all hOCR files in `input_dir` and write the results to `output_dir`, keeping the
+/// directory structure and replacing each file's extension with the converter's.
+///
+/// Returns the paths of the written files. Files that would be written to the same output,
+/// such as `a.hocr` and `a.html`, are not converted and fail with
+/// [`HOCRParserError::OutputCollision`].
+pub fn convert_dir<P: AsRef<Path>, Q: AsRef<Path>>(
+    input_dir: P,
+    output_dir: Q,
+    converter: Converter,
+) -> Result<BatchReport<PathBuf>> {
+    let input_dir = input_dir.as_ref();
+    let output_dir = output_dir.as_ref();
+    let files = find_files(input_dir)?;
+
+    let output_path = |path: &Path| {
+        let relative = path.strip_prefix(input_dir).unwrap_or(path);
+        let name = relative
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+        let stem =
+            Path::new(strip_compressed_extension(&name)).with_extension(converter.extension());
+        output_dir.join(relative).with_file_name(stem)
+    };
+    let mut outputs: HashMap<PathBuf, usize> = HashMap::new();
+    for path in &files {
+        *outputs.entry(output_path(path)).or_default() += 1;
+    }
+
+    Ok(process(&files, |path, hocr| {
+        let output = output_path(path);
+        if outputs[&output] > 1 {
+            return Err(HOCRParserError::OutputCollision(output));
+        }
+
+        if let Some(parent) = output.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&output, converter.convert(&hocr))?;
+        Ok(output)
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_directory() {
+        let root = std::env::temp_dir().join(format!("hocr-batch-{}", std::process::id()));
+        let input = root.join("in");
+        let output = root.join("out");
+        fs::create_dir_all(input.join("book")).unwrap();
+        fs::copy(
+            "examples/data/paper-image.hocr",
+            input.join("book/0001.hocr"),
+        )
+        .unwrap();
+        fs::write(input.join("book/0002.html"), "<html/>").unwrap();
+        fs::write(input.join("book/notes.txt"), "not hOCR").unwrap();
+        fs::copy(
+            "examples/data/paper-image.hocr",
+            input.join("book/0003.hocr"),
+        )
+        .unwrap();
+        fs::copy(
+            "examples/data/paper-image.hocr",
+            input.join("book/0003.html"),
+        )
+        .unwrap();
+
+        let report = convert_dir(&input, &output, Converter::Text).unwrap();
+        let written = output.join("book/0003.txt").exists();
+        fs::remove_dir_all(&root).ok();
+
+        assert_eq!(report.len(), 4);
+        assert_eq!(report.succeeded.len(), 1);
+        assert_eq!(report.succeeded[0].1, output.join("book/0001.txt"));
+        assert_eq!(report.failed[0].0, input.join("book/0002.html"));
+        assert!(matches!(report.failed[0].1, HOCRParserError::NoHeadElement));
+        assert!(matches!(
+            &report.failed[1].1,
+            HOCRParserError::OutputCollision(path) if *path == output.join("book/0003.txt")
+        ));
+        assert_eq!(report.failed[2].0, input.join("book/0003.html"));
+        assert!(!written);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn find_files_through_symlink_loop() {
+        let root = std::env::temp_dir().join(format!("hocr-walk-{}", std::process::id()));
+        fs::create_dir_all(root.join("a")).unwrap();
+        fs::write(root.join("a/page.hocr"), "").unwrap();
+        std::os::unix::fs::symlink(&root, root.join("a/loop")).unwrap();
+
+        let files = find_files(&root);
+        fs::remove_dir_all(&root).ok();
+        assert_eq!(files.unwrap(), [root.join("a/page.hocr")]);
+    }
+
+    #[test]
+    fn glob_example_files() {
+        let files = glob_files("examples/data/*.hocr").unwrap();
+        assert!(files.contains(&PathBuf::from("examples/data/paper-image.hocr")));
+
+        let report = process(&files, |_, hocr| Ok(hocr.pages().count()));
+        assert!(report.is_success());
+        assert!(glob_files("examples/[").is_err());
+    }
+}
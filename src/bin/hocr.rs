@@ -0,0 +1,495 @@
+//! Command-line tool for inspecting, validating and converting hOCR files.
+
+use std::{
+    fs,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use hocr_parser::{
+    json,
+    spec_definitions::elements::{OCRX_WORD, OCR_PAGE},
+    stats, Element, MergeOptions, Unit, Units, HOCR,
+};
+
+#[derive(Parser)]
+#[command(
+    name = "hocr",
+    version,
+    about = "Inspect, validate and convert hOCR files"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the plain text of hOCR files.
+    Text {
+        /// Input files; reads standard input if none are given or for `-`.
+        files: Vec<PathBuf>,
+    },
+    /// Print hOCR files in the JSON layout of `hocr_parser::json`.
+    Json {
+        /// Print without indentation.
+        #[arg(long)]
+        compact: bool,
+        #[command(flatten)]
+        units: UnitArgs,
+        /// Input files; reads standard input if none are given or for `-`.
+        files: Vec<PathBuf>,
+    },
+    /// Validate hOCR files, exiting with a non-zero status if any file is invalid.
+    Validate {
+        /// Input files; reads standard input if none are given or for `-`.
+        files: Vec<PathBuf>,
+    },
+    /// Print document statistics and word confidence statistics.
+    Stats {
+        /// Words with a confidence below this value are reported as low-confidence.
+        #[arg(short, long, default_value_t = 60.0)]
+        threshold: f64,
+        /// Print statistics as JSON.
+        #[arg(long)]
+        json: bool,
+        /// Input files; reads standard input if none are given or for `-`.
+        files: Vec<PathBuf>,
+    },
+    /// Print the lines (or words) whose text contains a pattern.
+    Grep {
+        /// Match case-insensitively.
+        #[arg(short, long)]
+        ignore_case: bool,
+        /// Match individual words instead of lines.
+        #[arg(short, long)]
+        words: bool,
+        #[command(flatten)]
+        units: UnitArgs,
+        pattern: String,
+        /// Input files; reads standard input if none are given or for `-`.
+        files: Vec<PathBuf>,
+    },
+    /// Convert an hOCR file to another format.
+    Convert {
+        /// Output format.
+        #[arg(short, long, value_enum, default_value_t = Format::Hocr)]
+        to: Format,
+        #[command(flatten)]
+        units: UnitArgs,
+        /// Output file; writes to standard output if not given.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Input file; reads standard input if not given or `-`.
+        file: Option<PathBuf>,
+    },
+    /// Split a multi-page hOCR file into one file per page.
+    Split {
+        /// Directory to write the page files to.
+        #[arg(short = 'd', long, default_value = ".")]
+        output_dir: PathBuf,
+        /// File name prefix of the page files.
+        #[arg(short, long, default_value = "page")]
+        prefix: String,
+        /// Input file; reads standard input if not given or `-`.
+        file: Option<PathBuf>,
+    },
+    /// Merge hOCR files into a single document.
+    Merge {
+        /// Output file; writes to standard output if not given.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Merge files produced by different OCR systems, keeping the first `ocr-system`.
+        #[arg(long)]
+        allow_mixed_systems: bool,
+        /// Input files, in page order.
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    /// hOCR XHTML.
+    Hocr,
+    /// JSON.
+    Json,
+    /// Plain text.
+    Text,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum BoxUnit {
+    /// Pixels, as in the hOCR file.
+    Px,
+    /// Points.
+    Pt,
+    /// Inches.
+    In,
+    /// Millimetres.
+    Mm,
+}
+
+/// Options for printing coordinates in physical units.
+#[derive(Args)]
+struct UnitArgs {
+    /// Unit of boxes, polygons and font sizes, converted with the `scan_res` of each page. JSON
+    /// output in units other than `px` cannot be read back as hOCR.
+    #[arg(long, value_enum, default_value_t = BoxUnit::Px)]
+    units: BoxUnit,
+    /// Resolution in dots per inch assumed for pages without a `scan_res`.
+    #[arg(long, default_value_t = Units::DEFAULT_DPI, value_parser = parse_dpi)]
+    dpi: f64,
+}
+
+impl UnitArgs {
+    /// The units of pages without a `scan_res`.
+    fn default(&self) -> Units {
+        Units::with_dpi(self.dpi).unwrap_or_default()
+    }
+}
+
+fn parse_dpi(s: &str) -> Result<f64, String> {
+    let dpi = s.parse::<f64>().map_err(|e| e.to_string())?;
+    match Units::with_dpi(dpi) {
+        Some(_) => Ok(dpi),
+        None => Err("must be a positive number".to_string()),
+    }
+}
+
+impl From<BoxUnit> for Unit {
+    fn from(unit: BoxUnit) -> Self {
+        match unit {
+            BoxUnit::Px => Unit::Pixel,
+            BoxUnit::Pt => Unit::Point,
+            BoxUnit::In => Unit::Inch,
+            BoxUnit::Mm => Unit::Millimetre,
+        }
+    }
+}
+
+type CliResult<T> = Result<T, String>;
+
+fn display_name(path: &Path) -> String {
+    if is_stdin(path) {
+        "<stdin>".to_string()
+    } else {
+        path.display().to_string()
+    }
+}
+
+fn is_stdin(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+fn inputs(files: Vec<PathBuf>) -> Vec<PathBuf> {
+    if files.is_empty() {
+        vec![PathBuf::from("-")]
+    } else {
+        files
+    }
+}
+
+/// Read an hOCR document, or its JSON representation if the input starts with `{`.
+fn read(path: &Path) -> CliResult<HOCR> {
+    let mut bytes = vec![];
+    let read = if is_stdin(path) {
+        io::stdin().read_to_end(&mut bytes).map(|_| ())
+    } else {
+        fs::read(path).map(|b| bytes = b)
+    };
+    read.map_err(|e| format!("{}: {e}", display_name(path)))?;
+
+    if bytes.trim_ascii_start().starts_with(b"{") {
+        serde_json::from_slice(&bytes).map_err(|e| format!("{}: {e}", display_name(path)))
+    } else {
+        HOCR::from_bytes(&bytes).map_err(|e| format!("{}: {e}", display_name(path)))
+    }
+}
+
+fn write(output: Option<&Path>, contents: &str) -> CliResult<()> {
+    match output {
+        Some(path) if !is_stdin(path) => {
+            fs::write(path, contents).map_err(|e| format!("{}: {e}", path.display()))
+        }
+        // a closed pipe (e.g. `hocr text | head`) is not an error
+        _ => match io::stdout().write_all(contents.as_bytes()) {
+            Err(e) if e.kind() != io::ErrorKind::BrokenPipe => Err(e.to_string()),
+            _ => Ok(()),
+        },
+    }
+}
+
+fn to_json<T: serde::Serialize>(value: &T, compact: bool) -> CliResult<String> {
+    let json = if compact {
+        serde_json::to_string(value)
+    } else {
+        serde_json::to_string_pretty(value)
+    };
+    json.map(|j| j + "\n").map_err(|e| e.to_string())
+}
+
+/// The JSON layout of `hocr`, with coordinates converted to the unit of `units` and a `units`
+/// field naming it.
+fn json_with_units(hocr: &HOCR, units: &UnitArgs, compact: bool) -> CliResult<String> {
+    match Unit::from(units.units) {
+        Unit::Pixel => to_json(hocr, compact),
+        unit => to_json(&json::in_units(hocr, unit, units.default()), compact),
+    }
+}
+
+fn convert(hocr: &HOCR, format: Format, units: &UnitArgs) -> CliResult<String> {
+    if !matches!(format, Format::Json) && Unit::from(units.units) != Unit::Pixel {
+        return Err("--units only applies to JSON output".to_string());
+    }
+
+    match format {
+        Format::Hocr => Ok(hocr.to_xhtml()),
+        Format::Json => json_with_units(hocr, units, false),
+        Format::Text => Ok(hocr.plain_text() + "\n"),
+    }
+}
+
+/// Run `f` on every input file, reporting errors without stopping at the first one.
+fn for_each_file(
+    files: Vec<PathBuf>,
+    mut f: impl FnMut(&Path, HOCR) -> CliResult<bool>,
+) -> ExitCode {
+    let mut success = true;
+    for path in inputs(files) {
+        match read(&path).and_then(|hocr| f(&path, hocr)) {
+            Ok(ok) => success &= ok,
+            Err(e) => {
+                eprintln!("hocr: {e}");
+                success = false;
+            }
+        }
+    }
+
+    if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn format_stats(path: &Path, hocr: &HOCR, threshold: f64) -> String {
+    let summary = hocr.summary();
+    let confidence = stats::document_stats(hocr, threshold);
+
+    let mut out = format!("{}\n", display_name(path));
+    out += &format!("  pages: {}\n", summary.pages);
+    out += &format!("  lines: {}\n", summary.lines);
+    out += &format!("  words: {}\n", summary.words);
+    if let Some(height) = summary.average_word_height_px {
+        out += &format!("  average word height: {height:.1} px\n");
+    }
+    if !summary.languages.is_empty() {
+        let langs: Vec<_> = summary.languages.keys().map(String::as_str).collect();
+        out += &format!("  languages: {}\n", langs.join(" "));
+    }
+    if let (Some(mean), Some(median), Some(min)) =
+        (confidence.mean, confidence.median, confidence.min)
+    {
+        out += &format!("  confidence: mean {mean:.1}, median {median:.1}, min {min:.1}\n");
+        out += &format!(
+            "  words below {threshold}: {} of {}\n",
+            confidence.below_threshold, confidence.count
+        );
+    }
+    out
+}
+
+/// Options of the `grep` subcommand.
+struct GrepOptions<'a> {
+    pattern: &'a str,
+    ignore_case: bool,
+    words: bool,
+    unit: Unit,
+    /// The units of pages without a `scan_res`.
+    default: Units,
+}
+
+/// Returns the matching lines or words formatted as `file:id:bbox: text`, with the box in
+/// `options.unit`.
+fn grep(path: &Path, hocr: &HOCR, options: &GrepOptions) -> String {
+    let GrepOptions {
+        pattern,
+        ignore_case,
+        words,
+        unit,
+        default,
+    } = *options;
+    let normalize = |s: &str| {
+        if ignore_case {
+            s.to_lowercase()
+        } else {
+            s.to_string()
+        }
+    };
+    let pattern = normalize(pattern);
+    let is_line = |e: &Element| {
+        e.children.iter().any(|c| c.element_type == OCRX_WORD) && e.element_type != OCRX_WORD
+    };
+
+    let mut out = String::new();
+    let mut units = default;
+    for e in hocr.iter_document_order() {
+        if e.element_type == OCR_PAGE {
+            units = Units::of_page(e, default);
+        }
+
+        let candidate = if words {
+            e.element_type == OCRX_WORD
+        } else {
+            is_line(e)
+        };
+        if !candidate {
+            continue;
+        }
+
+        let text = e.plain_text();
+        if normalize(&text).contains(&pattern) {
+            let bbox = match (e.bbox(), unit) {
+                (Some(b), Unit::Pixel) => b.to_string(),
+                (Some(b), unit) => units
+                    .convert_bbox(&b, unit)
+                    .map(|v| format!("{v:.2}"))
+                    .join(" "),
+                (None, _) => String::new(),
+            };
+            out += &format!(
+                "{}:{}:{bbox}: {text}\n",
+                display_name(path),
+                e.id.as_deref().unwrap_or("")
+            );
+        }
+    }
+    out
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::Text { files } => for_each_file(files, |_, hocr| {
+            write(None, &(hocr.plain_text() + "\n"))?;
+            Ok(true)
+        }),
+        Command::Json {
+            compact,
+            units,
+            files,
+        } => for_each_file(files, |_, hocr| {
+            write(None, &json_with_units(&hocr, &units, compact)?)?;
+            Ok(true)
+        }),
+        Command::Validate { files } => for_each_file(files, |path, hocr| {
+            let issues = hocr.validate();
+            for issue in &issues {
+                eprintln!("{}: {issue}", display_name(path));
+            }
+            Ok(issues.is_empty())
+        }),
+        Command::Stats {
+            threshold,
+            json,
+            files,
+        } => for_each_file(files, |path, hocr| {
+            if json {
+                let report = serde_json::json!({
+                    "file": display_name(path),
+                    "summary": hocr.summary(),
+                    "confidence": stats::document_stats(&hocr, threshold),
+                    "pages": stats::page_stats(&hocr, threshold),
+                    "low_confidence_words": stats::low_confidence_words(&hocr, threshold),
+                });
+                write(None, &to_json(&report, false)?)?;
+            } else {
+                write(None, &format_stats(path, &hocr, threshold))?;
+            }
+            Ok(true)
+        }),
+        Command::Grep {
+            ignore_case,
+            words,
+            units,
+            pattern,
+            files,
+        } => {
+            let options = GrepOptions {
+                pattern: &pattern,
+                ignore_case,
+                words,
+                unit: units.units.into(),
+                default: units.default(),
+            };
+            let mut found = false;
+            let code = for_each_file(files, |path, hocr| {
+                let matches = grep(path, &hocr, &options);
+                found |= !matches.is_empty();
+                write(None, &matches)?;
+                Ok(true)
+            });
+            if found {
+                code
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+        Command::Convert {
+            to,
+            units,
+            output,
+            file,
+        } => {
+            let file = file.unwrap_or_else(|| PathBuf::from("-"));
+            for_each_file(vec![file], |_, hocr| {
+                write(output.as_deref(), &convert(&hocr, to, &units)?)?;
+                Ok(true)
+            })
+        }
+        Command::Split {
+            output_dir,
+            prefix,
+            file,
+        } => {
+            let file = file.unwrap_or_else(|| PathBuf::from("-"));
+            for_each_file(vec![file], |_, hocr| {
+                for (i, page) in hocr.split_pages().iter().enumerate() {
+                    let path = output_dir.join(format!("{prefix}_{:04}.hocr", i + 1));
+                    write(Some(&path), &page.to_xhtml())?;
+                }
+                Ok(true)
+            })
+        }
+        Command::Merge {
+            output,
+            allow_mixed_systems,
+            files,
+        } => {
+            let options = MergeOptions {
+                allow_conflicting_systems: allow_mixed_systems,
+                ..MergeOptions::default()
+            };
+            let documents: CliResult<Vec<HOCR>> = files.iter().map(|f| read(f)).collect();
+            match documents
+                .and_then(|d| HOCR::merge_with_options(d, options).map_err(|e| e.to_string()))
+            {
+                Ok(merged) => match write(output.as_deref(), &merged.to_xhtml()) {
+                    Ok(()) => ExitCode::SUCCESS,
+                    Err(e) => {
+                        eprintln!("hocr: {e}");
+                        ExitCode::FAILURE
+                    }
+                },
+                Err(e) => {
+                    eprintln!("hocr: {e}");
+                    ExitCode::FAILURE
+                }
+            }
+        }
+    }
+}
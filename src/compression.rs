@@ -0,0 +1,114 @@
+use std::borrow::Cow;
+#[cfg(any(feature = "gzip", feature = "zstd"))]
+use std::io::Read;
+
+use crate::error::Result;
+
+#[cfg(any(feature = "batch", feature = "zip", feature = "tar"))]
+const HOCR_EXTENSIONS: [&str; 4] = [".hocr", ".html", ".htm", ".xhtml"];
+#[cfg(any(feature = "batch", feature = "zip", feature = "tar"))]
+const COMPRESSED_EXTENSIONS: [&str; 2] = [".gz", ".zst"];
+
+/// Strip a `.gz` or `.zst` extension from a file name.
+#[cfg(any(feature = "batch", feature = "zip", feature = "tar"))]
+pub(crate) fn strip_compressed_extension(name: &str) -> &str {
+    COMPRESSED_EXTENSIONS
+        .iter()
+        .find_map(|ext| {
+            let stem = name.len().checked_sub(ext.len())?;
+            name.get(stem..)?
+                .eq_ignore_ascii_case(ext)
+                .then(|| &name[..stem])
+        })
+        .unwrap_or(name)
+}
+
+/// Whether a file name has a hOCR extension, optionally followed by `.gz` or `.zst`.
+#[cfg(any(feature = "batch", feature = "zip", feature = "tar"))]
+pub(crate) fn is_hocr_file_name(name: &str) -> bool {
+    let name = strip_compressed_extension(name).to_ascii_lowercase();
+    HOCR_EXTENSIONS.iter().any(|ext| name.ends_with(ext))
+}
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+#[cfg(feature = "gzip")]
+fn gunzip(bytes: &[u8]) -> Result<Vec<u8>> {
+    let mut decompressed = vec![];
+    flate2::read::MultiGzDecoder::new(bytes).read_to_end(&mut decompressed)?;
+    Ok(decompressed)
+}
+
+#[cfg(not(feature = "gzip"))]
+fn gunzip(_: &[u8]) -> Result<Vec<u8>> {
+    Err(crate::HOCRParserError::UnsupportedCompression("gzip"))
+}
+
+#[cfg(feature = "zstd")]
+fn unzstd(bytes: &[u8]) -> Result<Vec<u8>> {
+    let mut decompressed = vec![];
+    zstd::Decoder::new(bytes)?.read_to_end(&mut decompressed)?;
+    Ok(decompressed)
+}
+
+#[cfg(not(feature = "zstd"))]
+fn unzstd(_: &[u8]) -> Result<Vec<u8>> {
+    Err(crate::HOCRParserError::UnsupportedCompression("zstd"))
+}
+
+/// Decompress gzip or zstd data, detected by its magic bytes. Other data is returned as is.
+pub(crate) fn decompress(bytes: &[u8]) -> Result<Cow<'_, [u8]>> {
+    if bytes.starts_with(GZIP_MAGIC) {
+        gunzip(bytes).map(Cow::Owned)
+    } else if bytes.starts_with(ZSTD_MAGIC) {
+        unzstd(bytes).map(Cow::Owned)
+    } else {
+        Ok(Cow::Borrowed(bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::HOCR;
+
+    #[cfg(any(feature = "gzip", feature = "zstd"))]
+    const EXAMPLE: &str = "examples/data/paper-image.hocr";
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn read_gzip() {
+        use std::io::Write;
+
+        let xml = std::fs::read(EXAMPLE).unwrap();
+        let mut encoder = flate2::write::GzEncoder::new(vec![], flate2::Compression::fast());
+        encoder.write_all(&xml).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        assert_eq!(
+            HOCR::from_bytes(&compressed).unwrap(),
+            HOCR::from_bytes(&xml).unwrap()
+        );
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn read_zstd() {
+        let xml = std::fs::read(EXAMPLE).unwrap();
+        let compressed = zstd::encode_all(&xml[..], 0).unwrap();
+
+        assert_eq!(
+            HOCR::from_bytes(&compressed).unwrap(),
+            HOCR::from_bytes(&xml).unwrap()
+        );
+    }
+
+    #[cfg(not(feature = "gzip"))]
+    #[test]
+    fn gzip_requires_feature() {
+        assert!(matches!(
+            HOCR::from_bytes(&[0x1f, 0x8b, 0x08, 0x00]),
+            Err(crate::HOCRParserError::UnsupportedCompression("gzip"))
+        ));
+    }
+}
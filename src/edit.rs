@@ -0,0 +1,563 @@
+use std::collections::HashSet;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{
+    element::Element,
+    error::Result,
+    geometry::BBox,
+    hocr::HOCR,
+    merge::unique_id,
+    parsing::is_formatting_element,
+    property::Property,
+    recompute::children_bbox,
+    spec_definitions::{
+        elements::{OCRX_WORD, OCR_PAGE},
+        properties,
+    },
+    HOCRParserError,
+};
+
+/// Per-character properties of words and the number of values per character.
+const CHARACTER_PROPERTIES: [(&str, usize); 2] =
+    [(properties::X_BBOXES, 4), (properties::X_CONFS, 1)];
+
+fn find_path(elements: &[Element], id: &str) -> Option<Vec<usize>> {
+    for (i, e) in elements.iter().enumerate() {
+        if e.id.as_deref() == Some(id) {
+            return Some(vec![i]);
+        }
+        if let Some(mut path) = find_path(&e.children, id) {
+            path.insert(0, i);
+            return Some(path);
+        }
+    }
+    None
+}
+
+pub(crate) fn collect_ids(elements: &[Element], ids: &mut HashSet<String>) {
+    for e in elements {
+        ids.extend(e.id.clone());
+        collect_ids(&e.children, ids);
+    }
+}
+
+/// Set the `bbox` of a container to the union of its children's, unless it is a page, whose
+/// box is the image size, or it has no `bbox` to begin with.
+pub(crate) fn fit_to_children(e: &mut Element) {
+    if e.element_type == OCR_PAGE || e.bbox().is_none() {
+        return;
+    }
+    if let Some(bbox) = children_bbox(e) {
+        e.set_property(properties::BBOX, Property::BBox(bbox).values());
+    }
+}
+
+/// Number of characters of a word's text, counted as grapheme clusters like
+/// [`Element::glyphs`].
+fn character_count(text: &str) -> usize {
+    text.graphemes(true).count()
+}
+
+/// Returns the values of a per-character property if they match the number of characters.
+fn character_values(
+    e: &Element,
+    name: &str,
+    per_character: usize,
+    chars: usize,
+) -> Option<Vec<String>> {
+    e.property(name)
+        .filter(|values| values.len() == chars * per_character)
+        .map(<[String]>::to_vec)
+}
+
+impl Element {
+    /// Set the values of a property, replacing it in place if present and appending it otherwise.
+    pub fn set_property(&mut self, name: &str, values: Vec<String>) {
+        match self.properties.iter_mut().find(|(n, _)| n == name) {
+            Some((_, v)) => *v = values,
+            None => self.properties.push((name.to_string(), values)),
+        }
+    }
+
+    /// Remove a property, returning its values if it was present.
+    pub fn remove_property(&mut self, name: &str) -> Option<Vec<String>> {
+        let index = self.properties.iter().position(|(n, _)| n == name)?;
+        Some(self.properties.remove(index).1)
+    }
+
+    fn set_bbox(&mut self, bbox: Option<BBox>) {
+        match bbox {
+            Some(bbox) => self.set_property(properties::BBOX, Property::BBox(bbox).values()),
+            None => {
+                self.remove_property(properties::BBOX);
+            }
+        }
+    }
+
+    /// The innermost of the formatting elements wrapping the whole text of a word, e.g. the
+    /// `<em>` of `<strong><em>word</em></strong>`.
+    fn formatting_mut(&mut self) -> Option<&mut Element> {
+        let [child] = &mut self.children[..] else {
+            return None;
+        };
+        if self.text.is_some()
+            || child.tail.is_some()
+            || !is_formatting_element(&child.element_type, &child.tag_name)
+        {
+            return None;
+        }
+        if child.formatting_mut().is_some() {
+            return child.formatting_mut();
+        }
+        Some(child)
+    }
+
+    /// Replace the text of a word, dropping character-level child elements but keeping
+    /// formatting elements that wrap the whole word.
+    fn set_word_text(&mut self, text: String) {
+        match self.formatting_mut() {
+            Some(formatting) => {
+                formatting.text = Some(text);
+                formatting.children.clear();
+            }
+            None => {
+                self.text = Some(text);
+                self.children.clear();
+            }
+        }
+    }
+}
+
+/// Editing operations for correcting OCR results.
+///
+/// Elements are addressed by their `id` attribute. After each operation, the `bbox` of every
+/// ancestor of a changed element is recomputed as the union of its children's boxes; pages keep
+/// their box. Words are edited as plain text: character-level child elements such as
+/// `ocrx_cinfo` and formatting of part of a word are dropped, while formatting elements such as
+/// `<strong>` wrapping the whole word are kept. The per-character `x_bboxes` and `x_confs`
+/// properties are kept aligned with the new text where possible and removed otherwise.
+impl HOCR {
+    /// Returns the element with the given id.
+    pub fn find(&self, id: &str) -> Option<&Element> {
+        let path = find_path(&self.elements, id)?;
+        let (last, parent) = path.split_last()?;
+        let mut elements = &self.elements;
+        for &i in parent {
+            elements = &elements[i].children;
+        }
+        Some(&elements[*last])
+    }
+
+    /// Returns the element with the given id for modification.
+    ///
+    /// Unlike the other editing operations, changes made through the returned reference do not
+    /// update ancestor boxes.
+    pub fn find_mut(&mut self, id: &str) -> Option<&mut Element> {
+        let path = find_path(&self.elements, id)?;
+        Some(self.element_mut(&path))
+    }
+
+    fn path(&self, id: &str) -> Result<Vec<usize>> {
+        find_path(&self.elements, id)
+            .ok_or_else(|| HOCRParserError::ElementNotFound(id.to_string()))
+    }
+
+    fn siblings_mut(&mut self, parent: &[usize]) -> &mut Vec<Element> {
+        let mut elements = &mut self.elements;
+        for &i in parent {
+            elements = &mut elements[i].children;
+        }
+        elements
+    }
+
+    fn element_mut(&mut self, path: &[usize]) -> &mut Element {
+        let (last, parent) = path.split_last().expect("path is not empty");
+        &mut self.siblings_mut(parent)[*last]
+    }
+
+    /// Recompute the boxes of the element at `path` and all of its ancestors, innermost first.
+    fn fit_ancestors(&mut self, path: &[usize]) {
+        for depth in (1..=path.len()).rev() {
+            fit_to_children(self.element_mut(&path[..depth]));
+        }
+    }
+
+    fn word_path(&self, id: &str) -> Result<Vec<usize>> {
+        let path = self.path(id)?;
+        match self.find(id) {
+            Some(e) if e.element_type == OCRX_WORD => Ok(path),
+            _ => Err(HOCRParserError::InvalidEdit(format!(
+                "'{id}' is not an {OCRX_WORD}"
+            ))),
+        }
+    }
+
+    /// Replace the text of a word.
+    ///
+    /// `x_bboxes` and `x_confs` are kept if the number of characters, counted as grapheme
+    /// clusters, is unchanged.
+    pub fn set_text(&mut self, id: &str, text: &str) -> Result<()> {
+        let path = self.word_path(id)?;
+        let word = self.element_mut(&path);
+
+        let old = character_count(&word.text_content());
+        if old != character_count(text) {
+            for (name, _) in CHARACTER_PROPERTIES {
+                word.remove_property(name);
+            }
+        }
+        word.set_word_text(text.to_string());
+        Ok(())
+    }
+
+    /// Split a word into two at character index `at` and return the id of the new second word.
+    ///
+    /// `at` counts grapheme clusters, as [`Element::glyphs`] does, so a word is never split
+    /// between a letter and its combining marks.
+    ///
+    /// `bbox` is the box of the second word; the first word then ends where it starts. Without
+    /// it, the boxes are derived from `x_bboxes` if present, or by dividing the word's box in
+    /// proportion to the number of characters. Other properties are copied to the new word, and
+    /// the text following the word then follows the new word.
+    pub fn split_word(&mut self, id: &str, at: usize, bbox: Option<BBox>) -> Result<String> {
+        let path = self.word_path(id)?;
+        let mut ids = HashSet::new();
+        collect_ids(&self.elements, &mut ids);
+        let new_id = unique_id(id.to_string(), &mut ids);
+
+        let word = self.element_mut(&path);
+        let text = word.text_content();
+        let chars = character_count(&text);
+        let split = match text.grapheme_indices(true).nth(at) {
+            Some((index, _)) if at > 0 => index,
+            _ => {
+                return Err(HOCRParserError::InvalidEdit(format!(
+                    "cannot split '{id}' with {chars} characters at {at}"
+                )))
+            }
+        };
+
+        let mut second = word.clone();
+        second.id = Some(new_id.clone());
+        word.tail = None;
+        second.set_word_text(text[split..].to_string());
+        word.set_word_text(text[..split].to_string());
+
+        for (name, per_character) in CHARACTER_PROPERTIES {
+            match character_values(word, name, per_character, chars) {
+                Some(mut values) => {
+                    second.set_property(name, values.split_off(at * per_character));
+                    word.set_property(name, values);
+                }
+                None => {
+                    word.remove_property(name);
+                    second.remove_property(name);
+                }
+            }
+        }
+
+        let char_boxes = word
+            .property(properties::X_BBOXES)
+            .and_then(BBox::list_from_values)
+            .zip(
+                second
+                    .property(properties::X_BBOXES)
+                    .and_then(BBox::list_from_values),
+            );
+        let (first_bbox, second_bbox) = match (bbox, word.bbox(), char_boxes) {
+            (Some(b), Some(w), _) => (
+                Some(BBox::new(w.x0, w.y0, b.x0.clamp(w.x0, w.x1), w.y1)),
+                Some(b),
+            ),
+            (Some(b), None, _) => (None, Some(b)),
+            (None, _, Some((first, second))) => {
+                let union = |boxes: Vec<BBox>| boxes.into_iter().reduce(|a, b| a.union(&b));
+                (union(first), union(second))
+            }
+            (None, Some(w), None) => {
+                let x = w.x0 + (w.width() as i64 * at as i64 / chars as i64) as i32;
+                (
+                    Some(BBox::new(w.x0, w.y0, x, w.y1)),
+                    Some(BBox::new(x, w.y0, w.x1, w.y1)),
+                )
+            }
+            (None, None, None) => (None, None),
+        };
+        word.set_bbox(first_bbox);
+        second.set_bbox(second_bbox);
+
+        let (last, parent) = path.split_last().expect("path is not empty");
+        self.siblings_mut(parent).insert(last + 1, second);
+        self.fit_ancestors(parent);
+        Ok(new_id)
+    }
+
+    /// Merge a word with the word directly following it in the same parent.
+    ///
+    /// The texts are concatenated without a space, the boxes are joined and the lower of the
+    /// two `x_wconf` confidences is kept. The second word is removed, and the text following it
+    /// is appended to the text following the first.
+    pub fn merge_words(&mut self, first: &str, second: &str) -> Result<()> {
+        let first_path = self.word_path(first)?;
+        let second_path = self.word_path(second)?;
+        let (last, parent) = first_path.split_last().expect("path is not empty");
+        if second_path[..] != [parent, &[last + 1][..]].concat() {
+            return Err(HOCRParserError::InvalidEdit(format!(
+                "'{second}' does not directly follow '{first}'"
+            )));
+        }
+
+        let siblings = self.siblings_mut(parent);
+        let next = siblings.remove(last + 1);
+        let word = &mut siblings[*last];
+
+        let (text, next_text) = (word.text_content(), next.text_content());
+        for (name, per_character) in CHARACTER_PROPERTIES {
+            let values = character_values(word, name, per_character, character_count(&text));
+            let next_values =
+                character_values(&next, name, per_character, character_count(&next_text));
+            match values.zip(next_values) {
+                Some((values, next_values)) => {
+                    word.set_property(name, [values, next_values].concat())
+                }
+                None => {
+                    word.remove_property(name);
+                }
+            }
+        }
+
+        let bbox = match (word.bbox(), next.bbox()) {
+            (Some(a), Some(b)) => Some(a.union(&b)),
+            (a, b) => a.or(b),
+        };
+        word.set_bbox(bbox);
+        if let Some(confidence) = [word.confidence(), next.confidence()]
+            .into_iter()
+            .flatten()
+            .reduce(f64::min)
+        {
+            word.set_property(properties::X_WCONF, Property::XWconf(confidence).values());
+        }
+        word.set_word_text(text + &next_text);
+        word.tail = match (word.tail.take(), next.tail) {
+            (Some(tail), Some(next_tail)) => Some(tail + &next_tail),
+            (tail, next_tail) => tail.or(next_tail),
+        };
+
+        self.fit_ancestors(parent);
+        Ok(())
+    }
+
+    /// Remove an element with all of its descendants and return it.
+    pub fn remove(&mut self, id: &str) -> Result<Element> {
+        let path = self.path(id)?;
+        let (last, parent) = path.split_last().expect("path is not empty");
+        let element = self.siblings_mut(parent).remove(*last);
+        self.fit_ancestors(parent);
+        Ok(element)
+    }
+
+    /// Insert `element` as the child at `index` of the element with id `parent`.
+    pub fn insert_child(&mut self, parent: &str, index: usize, element: Element) -> Result<()> {
+        let path = self.path(parent)?;
+        let children = &mut self.element_mut(&path).children;
+        if index > children.len() {
+            return Err(HOCRParserError::InvalidEdit(format!(
+                "index {index} out of range for '{parent}' with {} children",
+                children.len()
+            )));
+        }
+        children.insert(index, element);
+        self.fit_ancestors(&path);
+        Ok(())
+    }
+
+    /// Move an element to position `index` among the children of the element with id `parent`,
+    /// e.g. to move a word to another line.
+    ///
+    /// `index` refers to the children of `parent` after the element has been removed.
+    pub fn reparent(&mut self, id: &str, parent: &str, index: usize) -> Result<()> {
+        let path = self.path(id)?;
+        let parent_path = self.path(parent)?;
+        if parent_path.starts_with(&path) {
+            return Err(HOCRParserError::InvalidEdit(format!(
+                "cannot move '{id}' into itself or its descendant '{parent}'"
+            )));
+        }
+
+        let mut children = self.element_mut(&parent_path).children.len();
+        if parent_path[..] == path[..path.len() - 1] {
+            children -= 1;
+        }
+        if index > children {
+            return Err(HOCRParserError::InvalidEdit(format!(
+                "index {index} out of range for '{parent}' with {children} children"
+            )));
+        }
+
+        let element = self.remove(id)?;
+        self.insert_child(parent, index, element)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::document;
+
+    fn doc() -> String {
+        document(
+            "ocr_page ocr_line ocrx_word",
+            r#"<div class="ocr_page" id="page_1" title="bbox 0 0 500 500">
+                <span class="ocr_line" id="line_1" title="bbox 10 10 200 30">
+                    <span class="ocrx_word" id="word_1" title="bbox 10 10 50 30; x_wconf 90">Helo</span>
+                    <span class="ocrx_word" id="word_2" title="bbox 60 10 100 30; x_wconf 80; x_confs 1 2 3">wor</span>
+                    <span class="ocrx_word" id="word_3" title="bbox 110 10 120 30; x_wconf 70">ld</span>
+                    <span class="ocrx_word" id="word_4" title="bbox 190 10 200 30; x_wconf 5">~</span>
+                </span>
+                <span class="ocr_line" id="line_2" title="bbox 10 50 100 70">
+                    <span class="ocrx_word" id="word_5" title="bbox 10 50 100 70">twowords</span>
+                </span>
+            </div>"#,
+        )
+    }
+
+    fn bbox(hocr: &HOCR, id: &str) -> Option<BBox> {
+        hocr.find(id).unwrap().bbox()
+    }
+
+    #[test]
+    fn edit_word_text() {
+        let mut hocr = HOCR::from_str(&doc()).unwrap();
+        hocr.set_text("word_1", "Hello").unwrap();
+        hocr.set_text("word_2", "wo_").unwrap();
+        assert_eq!(hocr.find("word_1").unwrap().text.as_deref(), Some("Hello"));
+        assert!(hocr.find("word_2").unwrap().property("x_confs").is_some());
+
+        hocr.set_text("word_2", "wor_").unwrap();
+        assert!(hocr.find("word_2").unwrap().property("x_confs").is_none());
+        assert!(matches!(
+            hocr.set_text("line_1", "x"),
+            Err(HOCRParserError::InvalidEdit(_))
+        ));
+        assert!(matches!(
+            hocr.set_text("word_9", "x"),
+            Err(HOCRParserError::ElementNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn split_and_merge_words() {
+        let mut hocr = HOCR::from_str(&doc()).unwrap();
+
+        let id = hocr.split_word("word_5", 3, None).unwrap();
+        assert_eq!(id, "word_5_2");
+        assert_eq!(bbox(&hocr, "word_5"), Some(BBox::new(10, 50, 43, 70)));
+        assert_eq!(bbox(&hocr, &id), Some(BBox::new(43, 50, 100, 70)));
+        assert_eq!(hocr.find(&id).unwrap().text.as_deref(), Some("words"));
+
+        let id = hocr
+            .split_word("word_2", 1, Some(BBox::new(75, 10, 100, 30)))
+            .unwrap();
+        assert_eq!(bbox(&hocr, "word_2"), Some(BBox::new(60, 10, 75, 30)));
+        assert_eq!(
+            hocr.find(&id).unwrap().property("x_confs").unwrap(),
+            ["2", "3"]
+        );
+
+        hocr.merge_words(&id, "word_3").unwrap();
+        let merged = hocr.find(&id).unwrap();
+        assert_eq!(merged.text.as_deref(), Some("orld"));
+        assert_eq!(merged.bbox(), Some(BBox::new(75, 10, 120, 30)));
+        assert_eq!(merged.confidence(), Some(70.0));
+        assert!(merged.property("x_confs").is_none());
+        assert!(hocr.find("word_3").is_none());
+        assert!(hocr.merge_words("word_1", &id).is_err());
+    }
+
+    #[test]
+    fn edit_words_with_combining_marks() {
+        // "été" with decomposed accents: three grapheme clusters, five chars
+        let xml = doc().replace(
+            r#"title="bbox 10 50 100 70">twowords"#,
+            "title=\"bbox 10 50 40 70; x_bboxes 10 50 20 70 20 50 30 70 30 50 40 70; x_confs 1 2 3\">e\u{301}te\u{301}",
+        );
+        let mut hocr = HOCR::from_str(&xml).unwrap();
+        assert_eq!(hocr.find("word_5").unwrap().glyphs().len(), 3);
+
+        let id = hocr.split_word("word_5", 1, None).unwrap();
+        let (first, second) = (hocr.find("word_5").unwrap(), hocr.find(&id).unwrap());
+        assert_eq!(first.text.as_deref(), Some("e\u{301}"));
+        assert_eq!(first.property("x_confs").unwrap(), ["1"]);
+        assert_eq!(second.property("x_confs").unwrap(), ["2", "3"]);
+        assert_eq!(second.bbox(), Some(BBox::new(20, 50, 40, 70)));
+
+        hocr.merge_words("word_5", &id).unwrap();
+        assert_eq!(
+            hocr.find("word_5").unwrap().property("x_confs").unwrap(),
+            ["1", "2", "3"]
+        );
+        hocr.set_text("word_5", "ete").unwrap();
+        assert!(hocr.find("word_5").unwrap().property("x_bboxes").is_some());
+    }
+
+    #[test]
+    fn edit_formatted_words() {
+        let xml = doc()
+            .replace(">Helo</span>", "><strong><em>Helo</em></strong></span>, ")
+            .replace(">wor</span>", "><em>wor</em></span>")
+            .replace(">ld</span>", ">l<b>d</b></span>; ");
+        let mut hocr = HOCR::from_str(&xml).unwrap();
+
+        hocr.set_text("word_1", "Hello").unwrap();
+        let word = hocr.find("word_1").unwrap();
+        assert_eq!(word.text_content(), "Hello");
+        assert_eq!(word.children[0].children[0].text.as_deref(), Some("Hello"));
+        assert!(word.font_info().bold);
+        assert_eq!(word.tail.as_deref().map(str::trim), Some(","));
+
+        hocr.merge_words("word_2", "word_3").unwrap();
+        let merged = hocr.find("word_2").unwrap();
+        assert_eq!(merged.children[0].text.as_deref(), Some("world"));
+        assert!(merged.font_info().italic);
+        assert_eq!(merged.tail.as_deref().map(str::trim), Some(";"));
+
+        let id = hocr.split_word("word_2", 3, None).unwrap();
+        assert_eq!(hocr.find("word_2").unwrap().tail, None);
+        let second = hocr.find(&id).unwrap();
+        assert_eq!(second.children[0].text.as_deref(), Some("ld"));
+        assert_eq!(second.tail.as_deref().map(str::trim), Some(";"));
+    }
+
+    #[test]
+    fn remove_and_move_update_ancestor_boxes() {
+        let mut hocr = HOCR::from_str(&doc()).unwrap();
+
+        hocr.remove("word_4").unwrap();
+        assert_eq!(bbox(&hocr, "line_1"), Some(BBox::new(10, 10, 120, 30)));
+        assert_eq!(bbox(&hocr, "page_1"), Some(BBox::new(0, 0, 500, 500)));
+
+        hocr.reparent("word_3", "line_2", 1).unwrap();
+        assert_eq!(bbox(&hocr, "line_1"), Some(BBox::new(10, 10, 100, 30)));
+        assert_eq!(bbox(&hocr, "line_2"), Some(BBox::new(10, 10, 120, 70)));
+        let line: Vec<_> = hocr
+            .find("line_2")
+            .unwrap()
+            .children
+            .iter()
+            .map(|e| e.id.as_deref())
+            .collect();
+        assert_eq!(line, [Some("word_5"), Some("word_3")]);
+
+        assert!(hocr.reparent("line_1", "word_1", 0).is_err());
+        assert!(hocr.reparent("word_1", "line_2", 3).is_err());
+
+        let word = hocr.remove("word_1").unwrap();
+        hocr.insert_child("line_2", 0, word).unwrap();
+        assert_eq!(
+            hocr.find("line_2").unwrap().children[0].id.as_deref(),
+            Some("word_1")
+        );
+    }
+}
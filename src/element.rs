@@ -1,17 +1,32 @@
 use roxmltree::Node;
 
-use crate::{parsing::{check_property_name, parse_properties}, spec_definitions::HOCR_ELEMENTS, HOCRParserError, Result};
+use crate::{
+    geometry::BBox,
+    parsing::{allows_formatting, check_element, check_property_name, parse_properties},
+    traits::HocrElement,
+    ErrorLocation, HOCRParserError, Result,
+};
+
+/// Attributes stored in dedicated fields of [`Element`] and [`ElementBorrowed`].
+pub(crate) const RESERVED_ATTRIBUTES: [&str; 4] = ["class", "id", "title", "lang"];
 
 /// Represents an hOCR element, borrowing its contents from the XML string.
+///
+/// With the `serde` feature, elements are serialized in the JSON layout described in [`crate::json`].
 #[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ElementBorrowed<'a> {
-    #[cfg_attr(feature = "serde", serde(skip))]
     pub node: roxmltree::Node<'a, 'a>,
     pub element_type: &'a str,
+    pub id: Option<&'a str>,
+    pub tag_name: &'a str,
     pub properties: Vec<(&'a str, Vec<&'a str>)>,
     pub lang: Option<&'a str>,
+    /// Attributes other than `class`, `id`, `title` and `lang`.
+    pub attributes: Vec<(&'a str, &'a str)>,
     pub text: Option<&'a str>,
+    /// The text following the element, before its next sibling element, such as the `!` in
+    /// `<span class="ocrx_word">Hel<em>lo</em>!</span>`.
+    pub tail: Option<&'a str>,
     pub children: Vec<ElementBorrowed<'a>>,
 }
 
@@ -26,34 +41,35 @@ impl<'a> ElementBorrowed<'a> {
         let element_type = n.attribute("class").unwrap_or("");
 
         // check if defined in spec or whether it is implementation specific
-        if !(HOCR_ELEMENTS.contains(&element_type) || element_type.starts_with("ocrx_")) {
+        let in_word = n.parent_element().is_some_and(|p| {
+            allows_formatting(p.attribute("class").unwrap_or(""), p.tag_name().name())
+        });
+        if !check_element(element_type, n.tag_name().name(), in_word) {
             let pos = n.document().text_pos_at(n.range().start);
-            return Err(HOCRParserError::UnknownElement(pos));
+            return Err(HOCRParserError::UnknownElement(ErrorLocation::TextPos(pos)));
         }
 
         let prop = n.attribute("title").unwrap_or("");
         let properties = parse_properties(prop);
 
         for (name, _) in &properties {
-            if check_property_name(&name) {
+            if !check_property_name(name) {
                 let pos = n.document().text_pos_at(n.range().start);
-                return Err(HOCRParserError::UnknownProperty(pos));
+                return Err(HOCRParserError::UnknownProperty(ErrorLocation::TextPos(
+                    pos,
+                )));
             }
         }
 
         let lang = n.attribute("lang");
-        let text = n.text().unwrap_or("");
-
+        let attributes = n
+            .attributes()
+            .filter(|a| a.namespace().is_none() && !RESERVED_ATTRIBUTES.contains(&a.name()))
+            .map(|a| (a.name(), a.value()))
+            .collect();
         // prevent empty lines of whitespace
-        let processsed_text = {
-            let all_whitespace = text.chars().all(char::is_whitespace);
-
-            if all_whitespace {
-                None
-            } else {
-                Some(text)
-            }
-        };
+        let non_blank =
+            |text: Option<&'a str>| text.filter(|t| !t.chars().all(char::is_whitespace));
 
         let children: Vec<_> = n
             .children()
@@ -68,22 +84,40 @@ impl<'a> ElementBorrowed<'a> {
         Ok(Self {
             node: n,
             element_type,
+            id: n.attribute("id"),
+            tag_name: n.tag_name().name(),
             properties,
             lang,
-            text: processsed_text,
+            attributes,
+            text: non_blank(n.text()),
+            tail: non_blank(n.tail()),
             children: children.into_iter().map(Result::unwrap).collect(),
         })
     }
 }
 
 /// Represents an hOCR element.
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+///
+/// With the `serde` feature, elements are (de)serialized in the JSON layout described in [`crate::json`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "crate::json::JsonElement", into = "crate::json::JsonElement")
+)]
 pub struct Element {
     pub element_type: String,
+    pub id: Option<String>,
+    /// Name of the XHTML tag, e.g. `div` or `span`. May be empty for constructed elements.
+    pub tag_name: String,
     pub properties: Vec<(String, Vec<String>)>,
     pub lang: Option<String>,
+    /// Attributes other than `class`, `id`, `title` and `lang`.
+    pub attributes: Vec<(String, String)>,
     pub text: Option<String>,
+    /// The text following the element, before its next sibling element, such as the `!` in
+    /// `<span class="ocrx_word">Hel<em>lo</em>!</span>`.
+    pub tail: Option<String>,
     pub children: Vec<Element>,
 }
 
@@ -92,13 +126,21 @@ impl Element {
     pub fn from_element_borrowed(e: &ElementBorrowed) -> Self {
         Self {
             element_type: e.element_type.to_string(),
+            id: e.id.map(|i| i.to_string()),
+            tag_name: e.tag_name.to_string(),
             properties: e
                 .properties
                 .iter()
                 .map(|(k, v)| (k.to_string(), v.iter().map(|s| s.to_string()).collect()))
                 .collect(),
             lang: e.lang.map(|l| l.to_string()),
+            attributes: e
+                .attributes
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
             text: e.text.map(|t| t.to_string()),
+            tail: e.tail.map(|t| t.to_string()),
             children: e
                 .children
                 .iter()
@@ -113,3 +155,47 @@ impl Element {
         Ok(Self::from_element_borrowed(&e))
     }
 }
+
+impl<'a> ElementBorrowed<'a> {
+    /// Returns the values of the property with the given name, if present.
+    pub fn property(&self, name: &str) -> Option<&[&'a str]> {
+        HocrElement::property(self, name)
+    }
+
+    /// Returns the parsed `bbox` property, if present and well-formed.
+    pub fn bbox(&self) -> Option<BBox> {
+        HocrElement::bbox(self)
+    }
+
+    /// Returns the parsed `x_wconf` property, if present and well-formed.
+    pub fn confidence(&self) -> Option<f64> {
+        HocrElement::confidence(self)
+    }
+
+    /// Returns the text of this element and all of its descendants, concatenated.
+    pub fn text_content(&self) -> String {
+        HocrElement::text_content(self)
+    }
+}
+
+impl Element {
+    /// Returns the values of the property with the given name, if present.
+    pub fn property(&self, name: &str) -> Option<&[String]> {
+        HocrElement::property(self, name)
+    }
+
+    /// Returns the parsed `bbox` property, if present and well-formed.
+    pub fn bbox(&self) -> Option<BBox> {
+        HocrElement::bbox(self)
+    }
+
+    /// Returns the parsed `x_wconf` property, if present and well-formed.
+    pub fn confidence(&self) -> Option<f64> {
+        HocrElement::confidence(self)
+    }
+
+    /// Returns the text of this element and all of its descendants, concatenated.
+    pub fn text_content(&self) -> String {
+        HocrElement::text_content(self)
+    }
+}
@@ -0,0 +1,249 @@
+use std::{borrow::Cow, fs, io::Read, path::Path};
+
+use crate::{compression::decompress, error::Result, hocr::HOCR, HOCRParserError};
+
+/// Number of bytes searched for an XML declaration or `<meta>` charset.
+const PRESCAN_BYTES: usize = 4096;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    Latin1,
+}
+
+impl Encoding {
+    fn from_label(label: &str) -> Result<Self> {
+        match label.trim().to_ascii_lowercase().as_str() {
+            "utf-8" | "utf8" | "us-ascii" | "ascii" => Ok(Self::Utf8),
+            "utf-16le" => Ok(Self::Utf16Le),
+            "utf-16" | "utf-16be" => Ok(Self::Utf16Be),
+            "iso-8859-1" | "iso8859-1" | "iso_8859-1" | "latin1" | "latin-1" | "l1" => {
+                Ok(Self::Latin1)
+            }
+            _ => Err(HOCRParserError::UnsupportedEncoding(label.to_string())),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Utf8 => "UTF-8",
+            Self::Utf16Le => "UTF-16LE",
+            Self::Utf16Be => "UTF-16BE",
+            Self::Latin1 => "ISO-8859-1",
+        }
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+/// Returns the value following `name=` in `s`, with or without quotes.
+fn attribute_value<'a>(s: &'a [u8], name: &[u8]) -> Option<&'a [u8]> {
+    let start = find(s, name)? + name.len();
+    let rest = s[start..]
+        .trim_ascii_start()
+        .strip_prefix(b"=")?
+        .trim_ascii_start();
+    match rest.first()? {
+        quote @ (b'"' | b'\'') => {
+            let rest = &rest[1..];
+            Some(&rest[..rest.iter().position(|b| b == quote)?])
+        }
+        _ => {
+            let end = rest
+                .iter()
+                .position(|b| b.is_ascii_whitespace() || b"\"';>/".contains(b))
+                .unwrap_or(rest.len());
+            Some(&rest[..end])
+        }
+    }
+}
+
+/// Find the encoding declared in the XML declaration or a `<meta>` tag.
+fn declared_encoding(bytes: &[u8]) -> Option<String> {
+    let prolog = bytes[..bytes.len().min(PRESCAN_BYTES)].to_ascii_lowercase();
+    let label = |value: &[u8]| String::from_utf8_lossy(value).into_owned();
+
+    if prolog.starts_with(b"<?xml") {
+        let end = find(&prolog, b"?>")?;
+        if let Some(encoding) = attribute_value(&prolog[..end], b"encoding") {
+            return Some(label(encoding));
+        }
+    }
+
+    // covers both <meta charset="..."> and <meta http-equiv="Content-Type" content="...; charset=...">
+    let mut offset = 0;
+    while let Some(start) = find(&prolog[offset..], b"<meta") {
+        let start = offset + start;
+        let end = start + find(&prolog[start..], b">")?;
+        if let Some(charset) = attribute_value(&prolog[start..end], b"charset") {
+            return Some(label(charset));
+        }
+        offset = end;
+    }
+
+    None
+}
+
+fn decode_utf16(bytes: &[u8], encoding: Encoding) -> Result<String> {
+    let invalid = |offset| HOCRParserError::InvalidEncoding {
+        encoding: encoding.name(),
+        offset,
+    };
+    if bytes.len() % 2 != 0 {
+        return Err(invalid(bytes.len() - 1));
+    }
+
+    let units = bytes.chunks_exact(2).map(|c| match encoding {
+        Encoding::Utf16Le => u16::from_le_bytes([c[0], c[1]]),
+        _ => u16::from_be_bytes([c[0], c[1]]),
+    });
+
+    let mut decoded = String::with_capacity(bytes.len() / 2);
+    let mut offset = 0;
+    for c in char::decode_utf16(units) {
+        let c = c.map_err(|_| invalid(offset))?;
+        decoded.push(c);
+        offset += 2 * c.len_utf16();
+    }
+    Ok(decoded)
+}
+
+/// Decode hOCR bytes to a string, honouring a byte order mark, the XML declaration or a
+/// `<meta>` charset, in that order, and defaulting to UTF-8.
+pub(crate) fn decode(input: &[u8]) -> Result<Cow<'_, str>> {
+    let (encoding, bytes) = if let Some(rest) = input.strip_prefix(b"\xEF\xBB\xBF") {
+        (Encoding::Utf8, rest)
+    } else if let Some(rest) = input.strip_prefix(b"\xFF\xFE") {
+        (Encoding::Utf16Le, rest)
+    } else if let Some(rest) = input.strip_prefix(b"\xFE\xFF") {
+        (Encoding::Utf16Be, rest)
+    } else {
+        let encoding = match declared_encoding(input) {
+            Some(label) => Encoding::from_label(&label)?,
+            None => Encoding::Utf8,
+        };
+        // a UTF-16 declaration can only be read from ASCII-compatible bytes if it is wrong
+        let encoding = match encoding {
+            Encoding::Utf16Le | Encoding::Utf16Be => Encoding::Utf8,
+            e => e,
+        };
+        (encoding, input)
+    };
+
+    let decoded = match encoding {
+        Encoding::Utf8 => std::str::from_utf8(bytes).map(Cow::Borrowed).map_err(|e| {
+            HOCRParserError::InvalidEncoding {
+                encoding: encoding.name(),
+                offset: e.valid_up_to(),
+            }
+        }),
+        Encoding::Latin1 => Ok(Cow::Owned(bytes.iter().map(|&b| char::from(b)).collect())),
+        Encoding::Utf16Le | Encoding::Utf16Be => decode_utf16(bytes, encoding).map(Cow::Owned),
+    };
+
+    // report offsets relative to the input, including the byte order mark
+    let bom = input.len() - bytes.len();
+    decoded.map_err(|e| match e {
+        HOCRParserError::InvalidEncoding { encoding, offset } => HOCRParserError::InvalidEncoding {
+            encoding,
+            offset: offset + bom,
+        },
+        e => e,
+    })
+}
+
+impl HOCR {
+    /// Create a new [`HOCR`] instance from hOCR bytes.
+    ///
+    /// The encoding is taken from a byte order mark, the XML declaration or a `<meta>` charset,
+    /// defaulting to UTF-8. UTF-8, UTF-16 (with a byte order mark) and ISO-8859-1 are supported.
+    ///
+    /// Gzip and zstd compressed input is detected by its magic bytes and decompressed if the
+    /// `gzip` or `zstd` feature is enabled.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let bytes = decompress(bytes)?;
+        Self::from_str(&decode(&bytes)?)
+    }
+
+    /// Create a new [`HOCR`] instance by reading hOCR from `reader`.
+    ///
+    /// See [`HOCR::from_bytes`] for how the encoding is determined.
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Self> {
+        let mut bytes = vec![];
+        reader.read_to_end(&mut bytes)?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// Create a new [`HOCR`] instance from a hOCR file.
+    ///
+    /// See [`HOCR::from_bytes`] for how the encoding is determined.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn document(prolog: &str, meta: &str, word: &str) -> String {
+        let body = format!(r#"<span class="ocrx_word" id="w1">{word}</span>"#);
+        prolog.to_string() + &testing::document_with_meta("ocrx_word", meta, &body)
+    }
+
+    fn word(hocr: &HOCR) -> &str {
+        hocr.elements[0].text.as_deref().unwrap()
+    }
+
+    #[test]
+    fn decode_latin1() {
+        let xml = document(r#"<?xml version="1.0" encoding="ISO-8859-1"?>"#, "", "café");
+        let bytes: Vec<u8> = xml.chars().map(|c| c as u8).collect();
+        assert_eq!(word(&HOCR::from_bytes(&bytes).unwrap()), "café");
+
+        let html = document(
+            "",
+            r#"<meta http-equiv="Content-Type" content="text/html; charset=latin1"/>"#,
+            "café",
+        );
+        let bytes: Vec<u8> = html.chars().map(|c| c as u8).collect();
+        assert_eq!(word(&HOCR::from_reader(&bytes[..]).unwrap()), "café");
+    }
+
+    #[test]
+    fn decode_utf16_with_bom() {
+        let xml = document("", "", "naïve");
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend(xml.encode_utf16().flat_map(u16::to_le_bytes));
+        assert_eq!(word(&HOCR::from_bytes(&bytes).unwrap()), "naïve");
+    }
+
+    #[test]
+    fn invalid_utf8_offset() {
+        let xml = document("", "", "x");
+        let offset = xml.find('x').unwrap();
+        let mut bytes = xml.into_bytes();
+        bytes[offset] = 0xFF;
+
+        assert!(matches!(
+            HOCR::from_bytes(&bytes),
+            Err(HOCRParserError::InvalidEncoding { offset: o, .. }) if o == offset
+        ));
+        assert!(matches!(
+            HOCR::from_bytes(b"<?xml version='1.0' encoding='KOI8-R'?>"),
+            Err(HOCRParserError::UnsupportedEncoding(_))
+        ));
+    }
+
+    #[test]
+    fn from_path_example() {
+        let hocr = HOCR::from_path("examples/data/paper-image.hocr").unwrap();
+        let xml = std::fs::read_to_string("examples/data/paper-image.hocr").unwrap();
+        assert_eq!(hocr, HOCR::from_str(&xml).unwrap());
+    }
+}
@@ -1,14 +1,38 @@
+use std::fmt;
+
 use thiserror::Error;
 
+/// Where an unknown element or property was found, as far as the parser knows it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorLocation {
+    /// Line and column in the XML source, from [`HOCR::from_str`](crate::HOCR::from_str).
+    TextPos(roxmltree::TextPos),
+    /// Byte offset in the input, from the streaming parser.
+    Byte(u64),
+    /// The offending class or property name, from the HTML5 parser, which does not track
+    /// positions.
+    Name(String),
+}
+
+impl fmt::Display for ErrorLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorLocation::TextPos(pos) => write!(f, "at {pos}"),
+            ErrorLocation::Byte(offset) => write!(f, "at byte {offset}"),
+            ErrorLocation::Name(name) => write!(f, "'{name}'"),
+        }
+    }
+}
+
 /// hOCR parsing error variants.
 #[derive(Error, Debug, Clone)]
 pub enum HOCRParserError {
     /// Unknown hOCR element.
-    #[error("Unknown element found in hOCR file at {0}: elements either must be defined in the spec or start with 'ocrx_' prefix")]
-    UnknownElement(roxmltree::TextPos),
+    #[error("Unknown element {0} found in hOCR file: elements either must be defined in the spec or start with 'ocrx_' prefix")]
+    UnknownElement(ErrorLocation),
     /// Unknown hOCR property.
-    #[error("Unknown property found in hOCR file at {0}: properties either must be defined in the spec or start with 'x_' prefix")]
-    UnknownProperty(roxmltree::TextPos),
+    #[error("Unknown property {0} found in hOCR file: properties either must be defined in the spec or start with 'x_' prefix")]
+    UnknownProperty(ErrorLocation),
     /// Cannot construct hOCR element from node: it is not of type Element.
     #[error("Cannot construct hOCR Element from node: it is not of type Element at {0}")]
     NodeIsNotElement(roxmltree::TextPos),
@@ -24,10 +48,55 @@ pub enum HOCRParserError {
     /// No OCR capabilities found in hOCR file metadata.
     #[error("No OCR capabilities found in hOCR file metadata; invalid hOCR according to spec")]
     NoOCRCapabilities,
+    /// XML error while streaming a document.
+    #[error("XML error in hOCR stream at byte {0}: {1}")]
+    XMLStreamError(u64, String),
+    /// Documents with different OCR systems cannot be merged.
+    #[error("Cannot merge hOCR documents from different OCR systems: '{0}' and '{1}'")]
+    ConflictingOCRSystems(String, String),
+    /// No documents were given to merge.
+    #[error("No hOCR documents to merge")]
+    NothingToMerge,
+    /// Invalid byte sequence for the encoding of a hOCR file.
+    #[error("Invalid {encoding} byte sequence in hOCR file at byte offset {offset}")]
+    InvalidEncoding {
+        encoding: &'static str,
+        offset: usize,
+    },
+    /// Encoding declared by a hOCR file is not supported.
+    #[error("Unsupported encoding '{0}' declared in hOCR file: supported are UTF-8, UTF-16 and ISO-8859-1")]
+    UnsupportedEncoding(String),
+    /// A compressed hOCR file was read without the feature for its compression format.
+    #[error("hOCR file is {0}-compressed; enable the '{0}' feature to read it")]
+    UnsupportedCompression(&'static str),
+    /// Error reading a zip or tar archive.
+    #[error("Archive error: {0}")]
+    ArchiveError(String),
+    /// Invalid glob pattern for batch processing.
+    #[error("Invalid glob pattern: {0}")]
+    InvalidGlob(String),
+    /// Several input files of a batch conversion would be written to the same output file.
+    #[error("Output file '{0}' would be written for more than one input file")]
+    OutputCollision(std::path::PathBuf),
+    /// No element with the given id exists in the document.
+    #[error("No element with id '{0}' found in hOCR document")]
+    ElementNotFound(String),
+    /// An editing operation could not be applied.
+    #[error("Invalid edit: {0}")]
+    InvalidEdit(String),
+    /// I/O error while reading a hOCR file.
+    #[error("I/O error: {0}")]
+    IOError(std::sync::Arc<std::io::Error>),
     /// XML parse error.
     #[error("roxmltree error: {0}")]
     XMLParseError(#[from] roxmltree::Error),
 }
 
+impl From<std::io::Error> for HOCRParserError {
+    fn from(e: std::io::Error) -> Self {
+        Self::IOError(std::sync::Arc::new(e))
+    }
+}
+
 /// A `Result` type alias using `HOCRParserError` instances as the error variant.
 pub type Result<T, E = HOCRParserError> = std::result::Result<T, E>;
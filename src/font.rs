@@ -0,0 +1,211 @@
+use crate::{
+    element::{Element, ElementBorrowed},
+    hocr::HOCR,
+    parsing::is_formatting_element,
+    spec_definitions::{elements::OCRX_WORD, properties},
+    traits::HocrElement,
+};
+
+/// Font of an element, merged from its `x_font` and `x_fsize` properties and from `<strong>`,
+/// `<b>`, `<em>` and `<i>` formatting.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FontInfo {
+    /// Font name from `x_font`.
+    pub name: Option<String>,
+    /// Font size in points from `x_fsize`.
+    pub size: Option<f64>,
+    pub bold: bool,
+    pub italic: bool,
+}
+
+fn formatting<E: HocrElement>(e: &E, font: &mut FontInfo) {
+    match e.tag_name() {
+        "strong" | "b" => font.bold = true,
+        "em" | "i" => font.italic = true,
+        _ => {}
+    }
+    for child in e.children() {
+        if is_formatting_element(child.element_type(), child.tag_name()) {
+            formatting(child, font);
+        }
+    }
+}
+
+impl FontInfo {
+    /// The font given by the element itself, without inheritance: its `x_font` and `x_fsize`,
+    /// and whether it is or its text is wrapped in bold or italic formatting elements.
+    pub fn of_element<E: HocrElement>(e: &E) -> Self {
+        let mut font = FontInfo {
+            name: e
+                .property(properties::X_FONT)
+                .and_then(|v| v.first())
+                .map(|v| v.as_ref().to_string()),
+            size: e
+                .property(properties::X_FSIZE)
+                .and_then(|v| v.first()?.as_ref().parse().ok()),
+            ..Default::default()
+        };
+        formatting(e, &mut font);
+        font
+    }
+
+    /// Fill in the name and size from `parent` where missing; bold and italic formatting of
+    /// the parent applies to its contents.
+    pub fn inherit(self, parent: &FontInfo) -> FontInfo {
+        FontInfo {
+            name: self.name.or_else(|| parent.name.clone()),
+            size: self.size.or(parent.size),
+            bold: self.bold || parent.bold,
+            italic: self.italic || parent.italic,
+        }
+    }
+}
+
+/// Apply `f` to every element with its font, inherited from its ancestors.
+pub(crate) fn visit_fonts<'a>(
+    elements: &'a [Element],
+    inherited: &FontInfo,
+    f: &mut impl FnMut(&'a Element, &FontInfo),
+) {
+    for e in elements {
+        let font = FontInfo::of_element(e).inherit(inherited);
+        f(e, &font);
+        visit_fonts(&e.children, &font, f);
+    }
+}
+
+/// Number of words and characters set in a font.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FontCount {
+    pub font: FontInfo,
+    pub words: usize,
+    pub characters: usize,
+}
+
+/// The fonts used by the words of a document, see [`HOCR::font_inventory`].
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FontInventory {
+    /// Fonts by decreasing number of characters.
+    pub fonts: Vec<FontCount>,
+}
+
+impl FontInventory {
+    /// The font used for the most characters, taken to be the font of the body text.
+    pub fn body(&self) -> Option<&FontInfo> {
+        self.fonts.first().map(|c| &c.font)
+    }
+
+    /// The size of `font` relative to the body text, if both sizes are known.
+    pub fn relative_size(&self, font: &FontInfo) -> Option<f64> {
+        let body = self.body()?.size.filter(|s| *s > 0.0)?;
+        Some(font.size? / body)
+    }
+}
+
+impl ElementBorrowed<'_> {
+    /// Returns the font given by this element itself, see [`FontInfo::of_element`].
+    pub fn font_info(&self) -> FontInfo {
+        FontInfo::of_element(self)
+    }
+}
+
+impl Element {
+    /// Returns the font given by this element itself, see [`FontInfo::of_element`].
+    pub fn font_info(&self) -> FontInfo {
+        FontInfo::of_element(self)
+    }
+}
+
+impl HOCR {
+    /// Returns the font of the element with the given id, inherited from its ancestors.
+    pub fn font_info(&self, id: &str) -> Option<FontInfo> {
+        let mut found = None;
+        visit_fonts(&self.elements, &FontInfo::default(), &mut |e, font| {
+            if found.is_none() && e.id.as_deref() == Some(id) {
+                found = Some(font.clone());
+            }
+        });
+        found
+    }
+
+    /// Count the words and characters set in each font, with fonts inherited from ancestors.
+    pub fn font_inventory(&self) -> FontInventory {
+        let mut fonts: Vec<FontCount> = vec![];
+        visit_fonts(&self.elements, &FontInfo::default(), &mut |e, font| {
+            if e.element_type != OCRX_WORD {
+                return;
+            }
+
+            let characters = e.plain_text().chars().count();
+            match fonts.iter_mut().find(|c| c.font == *font) {
+                Some(count) => {
+                    count.words += 1;
+                    count.characters += characters;
+                }
+                None => fonts.push(FontCount {
+                    font: font.clone(),
+                    words: 1,
+                    characters,
+                }),
+            }
+        });
+
+        // stable, so fonts with equal counts stay in document order
+        fonts.sort_by_key(|c| std::cmp::Reverse(c.characters));
+        FontInventory { fonts }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::document;
+
+    fn doc() -> String {
+        document(
+            "ocr_page ocr_line ocrx_word",
+            r#"<div class="ocr_page" id="page_1" title="bbox 0 0 500 500">
+                <span class="ocr_line" id="line_1" title="bbox 0 0 200 40; x_font &quot;Times&quot;; x_fsize 20">
+                    <span class="ocrx_word" id="word_1" title="bbox 0 0 100 40"><strong>Title</strong></span>
+                </span>
+                <span class="ocr_line" id="line_2" title="bbox 0 50 200 60; x_font &quot;Times&quot;; x_fsize 10">
+                    <span class="ocrx_word" id="word_2" title="bbox 0 50 100 60">body</span>
+                    <span class="ocrx_word" id="word_3" title="bbox 110 50 200 60"><strong><em>text</em></strong></span>
+                    <span class="ocrx_word" id="word_4" title="bbox 210 50 300 60; x_fsize 10">here</span>
+                </span>
+            </div>"#,
+        )
+    }
+
+    #[test]
+    fn inherit_fonts_and_formatting() {
+        let hocr = HOCR::from_str(&doc()).unwrap();
+        assert_eq!(hocr.plain_text(), "Title\nbody text here");
+        assert_eq!(
+            hocr.font_info("word_3"),
+            Some(FontInfo {
+                name: Some("Times".to_string()),
+                size: Some(10.0),
+                bold: true,
+                italic: true,
+            })
+        );
+        assert!(!hocr.font_info("line_1").unwrap().bold);
+
+        let reparsed = HOCR::from_str(&hocr.to_xhtml()).unwrap();
+        assert!(reparsed.font_info("word_1").unwrap().bold);
+    }
+
+    #[test]
+    fn inventory() {
+        let hocr = HOCR::from_str(&doc()).unwrap();
+        let inventory = hocr.font_inventory();
+        assert_eq!(inventory.fonts.len(), 3);
+        assert_eq!(inventory.fonts[0].words, 2);
+        assert_eq!(inventory.body().unwrap().size, Some(10.0));
+        assert_eq!(inventory.relative_size(&inventory.fonts[1].font), Some(2.0));
+    }
+}
@@ -0,0 +1,204 @@
+/// An axis-aligned bounding box in image pixel coordinates, as used by the `bbox` and `x_bboxes` properties.
+///
+/// `(x0, y0)` is the top-left corner and `(x1, y1)` the bottom-right corner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct BBox {
+    pub x0: i32,
+    pub y0: i32,
+    pub x1: i32,
+    pub y1: i32,
+}
+
+impl BBox {
+    /// Create a new [`BBox`] from its corner coordinates.
+    pub fn new(x0: i32, y0: i32, x1: i32, y1: i32) -> Self {
+        Self { x0, y0, x1, y1 }
+    }
+
+    /// Parse a [`BBox`] from exactly four property values.
+    pub fn from_values<S: AsRef<str>>(values: &[S]) -> Option<Self> {
+        match values {
+            [x0, y0, x1, y1] => Some(Self::new(
+                x0.as_ref().parse().ok()?,
+                y0.as_ref().parse().ok()?,
+                x1.as_ref().parse().ok()?,
+                y1.as_ref().parse().ok()?,
+            )),
+            _ => None,
+        }
+    }
+
+    /// Parse a list of [`BBox`]es from property values, four values per box.
+    pub fn list_from_values<S: AsRef<str>>(values: &[S]) -> Option<Vec<Self>> {
+        if values.len() % 4 != 0 {
+            return None;
+        }
+
+        values.chunks(4).map(Self::from_values).collect()
+    }
+
+    /// Width of the box in pixels.
+    pub fn width(&self) -> i32 {
+        self.x1 - self.x0
+    }
+
+    /// Height of the box in pixels.
+    pub fn height(&self) -> i32 {
+        self.y1 - self.y0
+    }
+
+    /// The same box with inverted coordinates swapped, so that `x0 <= x1` and `y0 <= y1`.
+    pub fn normalized(&self) -> BBox {
+        BBox::new(
+            self.x0.min(self.x1),
+            self.y0.min(self.y1),
+            self.x0.max(self.x1),
+            self.y0.max(self.y1),
+        )
+    }
+
+    /// The smallest box containing both `self` and `other`.
+    pub fn union(&self, other: &BBox) -> BBox {
+        BBox::new(
+            self.x0.min(other.x0),
+            self.y0.min(other.y0),
+            self.x1.max(other.x1),
+            self.y1.max(other.y1),
+        )
+    }
+}
+
+impl std::fmt::Display for BBox {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {} {}", self.x0, self.y0, self.x1, self.y1)
+    }
+}
+
+/// An affine transformation of image coordinates, mapping `(x, y)` to
+/// `(a * x + c * y + e, b * x + d * y + f)`.
+///
+/// Transformations are combined with [`Transform::then`], e.g. to rotate about a point.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub e: f64,
+    pub f: f64,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Transform {
+    /// The transformation that leaves every point in place.
+    pub fn identity() -> Self {
+        Self {
+            a: 1.0,
+            b: 0.0,
+            c: 0.0,
+            d: 1.0,
+            e: 0.0,
+            f: 0.0,
+        }
+    }
+
+    /// Move every point by `(dx, dy)`.
+    pub fn translate(dx: f64, dy: f64) -> Self {
+        Self {
+            e: dx,
+            f: dy,
+            ..Self::identity()
+        }
+    }
+
+    /// Scale about the origin.
+    pub fn scale(sx: f64, sy: f64) -> Self {
+        Self {
+            a: sx,
+            d: sy,
+            ..Self::identity()
+        }
+    }
+
+    /// Rotate about the origin by `degrees`, clockwise on the page since the y axis points down.
+    pub fn rotate(degrees: f64) -> Self {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        Self {
+            a: cos,
+            b: sin,
+            c: -sin,
+            d: cos,
+            ..Self::identity()
+        }
+    }
+
+    /// Rotate about `(x, y)` by `degrees`, clockwise on the page.
+    pub fn rotate_about(degrees: f64, x: f64, y: f64) -> Self {
+        Self::translate(-x, -y)
+            .then(&Self::rotate(degrees))
+            .then(&Self::translate(x, y))
+    }
+
+    /// The transformation applying `self` and then `next`.
+    pub fn then(&self, next: &Transform) -> Transform {
+        Transform {
+            a: next.a * self.a + next.c * self.b,
+            b: next.b * self.a + next.d * self.b,
+            c: next.a * self.c + next.c * self.d,
+            d: next.b * self.c + next.d * self.d,
+            e: next.a * self.e + next.c * self.f + next.e,
+            f: next.b * self.e + next.d * self.f + next.f,
+        }
+    }
+
+    /// The transformation undoing `self`, if it is invertible.
+    pub fn inverse(&self) -> Option<Transform> {
+        let det = self.a * self.d - self.b * self.c;
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+
+        let (a, b, c, d) = (self.d / det, -self.b / det, -self.c / det, self.a / det);
+        Some(Transform {
+            a,
+            b,
+            c,
+            d,
+            e: -(a * self.e + c * self.f),
+            f: -(b * self.e + d * self.f),
+        })
+    }
+
+    /// Map a point.
+    pub fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        (
+            self.a * x + self.c * y + self.e,
+            self.b * x + self.d * y + self.f,
+        )
+    }
+
+    /// The smallest box containing the mapped corners of `b`, rounded outwards to whole pixels.
+    pub fn apply_bbox(&self, b: &BBox) -> BBox {
+        let corners = [(b.x0, b.y0), (b.x1, b.y0), (b.x0, b.y1), (b.x1, b.y1)]
+            .map(|(x, y)| self.apply(x as f64, y as f64));
+        let (mut x0, mut y0) = (f64::INFINITY, f64::INFINITY);
+        let (mut x1, mut y1) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+        for (x, y) in corners {
+            x0 = x0.min(x);
+            y0 = y0.min(y);
+            x1 = x1.max(x);
+            y1 = y1.max(y);
+        }
+        // tolerate rounding errors, e.g. 99.99999999 from a rotation by 90 degrees
+        let floor = |v: f64| (v + 1e-6).floor() as i32;
+        let ceil = |v: f64| (v - 1e-6).ceil() as i32;
+        BBox::new(floor(x0), floor(y0), ceil(x1), ceil(y1))
+    }
+}
@@ -0,0 +1,231 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{
+    element::{Element, ElementBorrowed},
+    geometry::BBox,
+    spec_definitions::properties,
+};
+
+/// A single character (grapheme cluster) of an element's text with its bounding box and confidence.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Glyph {
+    pub text: String,
+    pub bbox: BBox,
+    /// Confidence from the `x_confs` property, if present.
+    pub confidence: Option<f64>,
+}
+
+/// A character segmentation cut decoded from the `cuts` property.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cut {
+    /// Absolute horizontal position of the cut at the bottom of the bounding box.
+    pub x: i32,
+    /// Corrections of a non-straight cut as absolute `(y, x)` points, walking upwards from the bottom of the bounding box.
+    pub corrections: Vec<(i32, i32)>,
+}
+
+impl Cut {
+    /// Leftmost horizontal position reached by the cut.
+    pub fn min_x(&self) -> i32 {
+        self.corrections
+            .iter()
+            .map(|(_, x)| *x)
+            .fold(self.x, i32::min)
+    }
+
+    /// Rightmost horizontal position reached by the cut.
+    pub fn max_x(&self) -> i32 {
+        self.corrections
+            .iter()
+            .map(|(_, x)| *x)
+            .fold(self.x, i32::max)
+    }
+}
+
+/// Decode the values of a `cuts` property relative to the element's bounding box.
+///
+/// Each value is the offset of a cut from the previous one (the first from the left edge of `bbox`).
+/// A cut that is not a straight vertical line is followed by comma-separated pairs of
+/// vertical and horizontal deltas, e.g. `7,8,-2` moves 8 pixels up from the bottom and then 2 pixels left.
+pub fn parse_cuts<S: AsRef<str>>(values: &[S], bbox: BBox) -> Option<Vec<Cut>> {
+    let mut x = bbox.x0;
+
+    values
+        .iter()
+        .map(|value| {
+            let mut parts = value.as_ref().split(',');
+            x += parts.next()?.parse::<i32>().ok()?;
+
+            let deltas: Vec<i32> = parts.map(|p| p.parse().ok()).collect::<Option<_>>()?;
+            if deltas.len() % 2 != 0 {
+                return None;
+            }
+
+            let (mut cy, mut cx) = (bbox.y1, x);
+            let corrections = deltas
+                .chunks(2)
+                .map(|d| {
+                    cy -= d[0];
+                    cx += d[1];
+                    (cy, cx)
+                })
+                .collect();
+
+            Some(Cut { x, corrections })
+        })
+        .collect()
+}
+
+fn parse_confs<S: AsRef<str>>(values: &[S]) -> Option<Vec<f64>> {
+    values.iter().map(|v| v.as_ref().parse().ok()).collect()
+}
+
+pub(crate) fn glyph_boxes<S: AsRef<str>>(
+    bbox: Option<BBox>,
+    x_bboxes: Option<&[S]>,
+    cuts: Option<&[S]>,
+) -> Vec<BBox> {
+    if let Some(boxes) = x_bboxes.and_then(BBox::list_from_values) {
+        return boxes;
+    }
+
+    let Some((bbox, cuts)) = bbox
+        .zip(cuts)
+        .and_then(|(b, c)| Some((b, parse_cuts(c, b)?)))
+    else {
+        return vec![];
+    };
+
+    let mut bounds: Vec<(i32, i32)> = cuts.iter().map(|c| (c.min_x(), c.max_x())).collect();
+    if bounds.first().is_none_or(|(min, _)| *min > bbox.x0) {
+        bounds.insert(0, (bbox.x0, bbox.x0));
+    }
+    if bounds.last().is_none_or(|(_, max)| *max < bbox.x1) {
+        bounds.push((bbox.x1, bbox.x1));
+    }
+
+    bounds
+        .windows(2)
+        .map(|w| BBox::new(w[0].0, bbox.y0, w[1].1, bbox.y1))
+        .collect()
+}
+
+fn glyphs<S: AsRef<str>>(
+    text: &str,
+    bbox: Option<BBox>,
+    x_bboxes: Option<&[S]>,
+    x_confs: Option<&[S]>,
+    cuts: Option<&[S]>,
+) -> Vec<Glyph> {
+    let boxes = glyph_boxes(bbox, x_bboxes, cuts);
+    let confs = x_confs.and_then(parse_confs).unwrap_or_default();
+
+    text.graphemes(true)
+        .filter(|g| !g.chars().all(char::is_whitespace))
+        .zip(boxes)
+        .enumerate()
+        .map(|(i, (g, bbox))| Glyph {
+            text: g.to_string(),
+            bbox,
+            confidence: confs.get(i).copied(),
+        })
+        .collect()
+}
+
+impl Element {
+    /// Returns the glyphs of this element by zipping the grapheme clusters of its text with
+    /// the boxes from the `x_bboxes` property (or, if missing, the decoded `cuts` property)
+    /// and the confidences from the `x_confs` property.
+    ///
+    /// Returns an empty vector if the element has no per-character geometry.
+    pub fn glyphs(&self) -> Vec<Glyph> {
+        glyphs(
+            &self.text_content(),
+            self.bbox(),
+            self.property(properties::X_BBOXES),
+            self.property(properties::X_CONFS),
+            self.property(properties::CUTS),
+        )
+    }
+}
+
+impl<'a> ElementBorrowed<'a> {
+    /// Returns the glyphs of this element by zipping the grapheme clusters of its text with
+    /// the boxes from the `x_bboxes` property (or, if missing, the decoded `cuts` property)
+    /// and the confidences from the `x_confs` property.
+    ///
+    /// Returns an empty vector if the element has no per-character geometry.
+    pub fn glyphs(&self) -> Vec<Glyph> {
+        glyphs(
+            &self.text_content(),
+            self.bbox(),
+            self.property(properties::X_BBOXES),
+            self.property(properties::X_CONFS),
+            self.property(properties::CUTS),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(title: &str, text: &str) -> Element {
+        let xml = format!(r#"<span class="ocrx_word" title="{title}">{text}</span>"#);
+        let doc = roxmltree::Document::parse(&xml).unwrap();
+        Element::from_node(doc.root_element()).unwrap()
+    }
+
+    #[test]
+    fn glyphs_from_x_bboxes_and_x_confs() {
+        let e = word(
+            "bbox 10 10 40 30; x_bboxes 10 10 20 30 20 12 30 30 30 10 40 28; x_confs 99.5 87 42.25",
+            "cat",
+        );
+        let glyphs = e.glyphs();
+        assert_eq!(glyphs.len(), 3);
+        assert_eq!(glyphs[1].text, "a");
+        assert_eq!(glyphs[1].bbox, BBox::new(20, 12, 30, 30));
+        assert_eq!(glyphs[2].confidence, Some(42.25));
+    }
+
+    #[test]
+    fn glyphs_zip_grapheme_clusters() {
+        let e = word("bbox 0 0 20 10; x_bboxes 0 0 10 10 10 0 20 10", "e\u{301}a");
+        let glyphs = e.glyphs();
+        assert_eq!(glyphs[0].text, "e\u{301}");
+        assert_eq!(glyphs[0].confidence, None);
+        assert_eq!(glyphs[1].text, "a");
+    }
+
+    #[test]
+    fn glyphs_from_interior_cuts() {
+        let e = word("bbox 100 0 130 20; cuts 10 10", "abc");
+        let boxes: Vec<_> = e.glyphs().into_iter().map(|g| g.bbox).collect();
+        assert_eq!(
+            boxes,
+            vec![
+                BBox::new(100, 0, 110, 20),
+                BBox::new(110, 0, 120, 20),
+                BBox::new(120, 0, 130, 20)
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_cuts_with_corrections() {
+        let cuts = parse_cuts(&["9", "11", "7,8,-2", "15"], BBox::new(0, 0, 50, 20)).unwrap();
+        assert_eq!(cuts[1].x, 20);
+        assert_eq!(cuts[2].x, 27);
+        assert_eq!(cuts[2].corrections, vec![(12, 25)]);
+        assert_eq!(cuts[2].min_x(), 25);
+        assert_eq!(cuts[3].x, 42);
+    }
+
+    #[test]
+    fn parse_cuts_rejects_odd_corrections() {
+        assert!(parse_cuts(&["7,8"], BBox::new(0, 0, 50, 20)).is_none());
+    }
+}
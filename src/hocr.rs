@@ -3,17 +3,29 @@ use roxmltree::{Node, ParsingOptions};
 use crate::error::Result;
 use crate::{element::Element, element::ElementBorrowed, HOCRParserError};
 
+/// Metadata names stored in dedicated fields of [`HOCR`] and [`HOCRBorrowed`].
+const TYPED_METADATA: [&str; 6] = [
+    "ocr-system",
+    "ocr-capabilities",
+    "ocr-number-of-pages",
+    "ocr-langs",
+    "ocr-scripts",
+    "scripts",
+];
+
 /// Represents a hOCR file, borrowing its contents from the XML string.
+///
+/// With the `serde` feature, documents are serialized in the versioned JSON layout described in [`crate::json`].
 #[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct HOCRBorrowed<'input> {
-    #[cfg_attr(feature = "serde", serde(skip))]
     pub document: &'input roxmltree::Document<'input>,
     pub system: &'input str,
     pub capabilities: Vec<&'input str>,
     pub number_of_pages: Option<u32>,
     pub langs: Option<Vec<&'input str>>,
     pub scripts: Option<Vec<&'input str>>,
+    /// Other `<meta>` name and content pairs from the document head.
+    pub metadata: Vec<(&'input str, &'input str)>,
     pub elements: Vec<ElementBorrowed<'input>>,
 }
 
@@ -54,8 +66,7 @@ impl<'input> HOCRBorrowed<'input> {
         let number_of_pages = metadata
             .iter()
             .find(|(name, _)| *name == "ocr-number-of-pages")
-            .map(|(_, content)| content.parse().ok())
-            .flatten();
+            .and_then(|(_, content)| content.parse().ok());
 
         let langs = metadata
             .iter()
@@ -64,9 +75,15 @@ impl<'input> HOCRBorrowed<'input> {
 
         let scripts = metadata
             .iter()
-            .find(|(name, _)| *name == "scripts")
+            .find(|(name, _)| *name == "ocr-scripts" || *name == "scripts")
             .map(|(_, content)| content.split_whitespace().collect());
 
+        let metadata = metadata
+            .iter()
+            .filter(|(name, _)| !TYPED_METADATA.contains(name))
+            .copied()
+            .collect();
+
         let body = document
             .root_element()
             .children()
@@ -84,36 +101,115 @@ impl<'input> HOCRBorrowed<'input> {
         }
 
         Ok(Self {
-            document: document,
+            document,
             system,
             capabilities,
             number_of_pages,
             langs,
             scripts,
+            metadata,
             elements: elements.into_iter().map(Result::unwrap).collect(),
         })
     }
 }
 
 /// Represents a hOCR file.
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+///
+/// With the `serde` feature, documents are (de)serialized in the versioned JSON layout described in [`crate::json`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "crate::json::JsonDocument", into = "crate::json::JsonDocument")
+)]
 pub struct HOCR {
     pub system: String,
     pub capabilities: Vec<String>,
     pub number_of_pages: Option<u32>,
     pub langs: Option<Vec<String>>,
     pub scripts: Option<Vec<String>>,
+    /// Other `<meta>` name and content pairs from the document head.
+    pub metadata: Vec<(String, String)>,
     pub elements: Vec<Element>,
 }
 
+/// Document-level metadata of a hOCR file, taken from the `<meta>` tags of its head.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DocumentMetadata {
+    pub system: String,
+    pub capabilities: Vec<String>,
+    pub number_of_pages: Option<u32>,
+    pub langs: Option<Vec<String>>,
+    pub scripts: Option<Vec<String>>,
+    /// Other `<meta>` name and content pairs from the document head.
+    pub metadata: Vec<(String, String)>,
+}
+
+impl DocumentMetadata {
+    /// Create a new [`DocumentMetadata`] instance from `<meta>` name and content pairs.
+    pub fn from_meta_tags<S: AsRef<str>>(tags: &[(S, S)]) -> Result<Self> {
+        let find = |names: &[&str]| {
+            tags.iter()
+                .find(|(name, _)| names.contains(&name.as_ref()))
+                .map(|(_, content)| content.as_ref())
+        };
+        let words = |content: &str| content.split_whitespace().map(str::to_string).collect();
+
+        Ok(Self {
+            system: find(&["ocr-system"])
+                .ok_or(HOCRParserError::NoOCRSystem)?
+                .to_string(),
+            capabilities: find(&["ocr-capabilities"])
+                .map(words)
+                .ok_or(HOCRParserError::NoOCRCapabilities)?,
+            number_of_pages: find(&["ocr-number-of-pages"]).and_then(|c| c.parse().ok()),
+            langs: find(&["ocr-langs"]).map(words),
+            scripts: find(&["ocr-scripts", "scripts"]).map(words),
+            metadata: tags
+                .iter()
+                .filter(|(name, _)| !TYPED_METADATA.contains(&name.as_ref()))
+                .map(|(n, c)| (n.as_ref().to_string(), c.as_ref().to_string()))
+                .collect(),
+        })
+    }
+}
+
 impl HOCR {
+    /// Returns a copy of the document-level metadata.
+    pub fn document_metadata(&self) -> DocumentMetadata {
+        DocumentMetadata {
+            system: self.system.clone(),
+            capabilities: self.capabilities.clone(),
+            number_of_pages: self.number_of_pages,
+            langs: self.langs.clone(),
+            scripts: self.scripts.clone(),
+            metadata: self.metadata.clone(),
+        }
+    }
+
+    /// Create a new [`HOCR`] instance from its metadata and elements.
+    pub fn from_parts(metadata: DocumentMetadata, elements: Vec<Element>) -> Self {
+        Self {
+            system: metadata.system,
+            capabilities: metadata.capabilities,
+            number_of_pages: metadata.number_of_pages,
+            langs: metadata.langs,
+            scripts: metadata.scripts,
+            metadata: metadata.metadata,
+            elements,
+        }
+    }
+
     /// Create a new [`HOCR`] instance from a string containing hOCR XML.
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(xml_str: &str) -> Result<Self> {
-        let mut options = ParsingOptions::default();
-        options.allow_dtd = true;
+        let options = ParsingOptions {
+            allow_dtd: true,
+            ..ParsingOptions::default()
+        };
 
-        let doc = roxmltree::Document::parse_with_options(&xml_str, options)?;
+        let doc = roxmltree::Document::parse_with_options(xml_str, options)?;
 
         let hocr = HOCRBorrowed::new_from_document(&doc)?;
         Ok(Self::from_hocr_borrowed(hocr))
@@ -131,6 +227,11 @@ impl HOCR {
             scripts: hocr
                 .scripts
                 .map(|s| s.iter().map(|s| s.to_string()).collect()),
+            metadata: hocr
+                .metadata
+                .iter()
+                .map(|(n, c)| (n.to_string(), c.to_string()))
+                .collect(),
             elements: hocr
                 .elements
                 .iter()
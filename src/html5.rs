@@ -0,0 +1,207 @@
+use html5ever::{parse_document, tendril::TendrilSink, ParseOpts};
+use markup5ever_rcdom::{Handle, NodeData, RcDom};
+
+use crate::{
+    element::{Element, RESERVED_ATTRIBUTES},
+    error::{ErrorLocation, Result},
+    hocr::{DocumentMetadata, HOCR},
+    parsing::{allows_formatting, check_element, check_property_name, parse_properties},
+    HOCRParserError,
+};
+
+fn tag_name(handle: &Handle) -> Option<&str> {
+    match &handle.data {
+        NodeData::Element { name, .. } => Some(&name.local),
+        _ => None,
+    }
+}
+
+fn child_elements(handle: &Handle) -> Vec<Handle> {
+    handle
+        .children
+        .borrow()
+        .iter()
+        .filter(|c| tag_name(c).is_some())
+        .cloned()
+        .collect()
+}
+
+fn find_child(handle: &Handle, tag: &str) -> Option<Handle> {
+    child_elements(handle)
+        .into_iter()
+        .find(|c| tag_name(c) == Some(tag))
+}
+
+fn attributes(handle: &Handle) -> Vec<(String, String)> {
+    match &handle.data {
+        NodeData::Element { attrs, .. } => attrs
+            .borrow()
+            .iter()
+            .filter(|a| a.name.ns.is_empty())
+            .map(|a| (a.name.local.to_string(), a.value.to_string()))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// The contents of a text node, unless it is whitespace only.
+fn non_blank_text(node: Option<&Handle>) -> Option<String> {
+    match &node?.data {
+        NodeData::Text { contents } => {
+            let text = contents.borrow().to_string();
+            (!text.chars().all(char::is_whitespace)).then_some(text)
+        }
+        _ => None,
+    }
+}
+
+/// Convert an element, where `in_word` tells whether its parent [`allows_formatting`].
+fn element_from_handle(handle: &Handle, in_word: bool) -> Result<Element> {
+    let mut attributes = attributes(handle);
+    let mut take = |name: &str| {
+        let index = attributes.iter().position(|(n, _)| n == name)?;
+        Some(attributes.remove(index).1)
+    };
+
+    let element_type = take("class").unwrap_or_default();
+    let tag = tag_name(handle).unwrap_or_default();
+    if !check_element(&element_type, tag, in_word) {
+        return Err(HOCRParserError::UnknownElement(ErrorLocation::Name(
+            element_type,
+        )));
+    }
+
+    let title = take("title").unwrap_or_default();
+    let properties = parse_properties(&title);
+    if let Some((name, _)) = properties
+        .iter()
+        .find(|(name, _)| !check_property_name(name))
+    {
+        return Err(HOCRParserError::UnknownProperty(ErrorLocation::Name(
+            name.to_string(),
+        )));
+    }
+
+    let id = take("id");
+    let lang = take("lang");
+    let is_reserved = |name: &str| RESERVED_ATTRIBUTES.contains(&name) || name == "xmlns";
+    attributes.retain(|(name, _)| !is_reserved(name));
+
+    let text = non_blank_text(handle.children.borrow().first());
+    let children = elements_from_children(handle, allows_formatting(&element_type, tag))?;
+
+    Ok(Element {
+        element_type,
+        id,
+        tag_name: tag.to_string(),
+        properties: properties
+            .into_iter()
+            .map(|(name, values)| {
+                (
+                    name.to_string(),
+                    values.into_iter().map(str::to_string).collect(),
+                )
+            })
+            .collect(),
+        lang,
+        attributes,
+        text,
+        tail: None,
+        children,
+    })
+}
+
+/// Convert the child elements of `handle`, keeping the text following each as its tail.
+fn elements_from_children(handle: &Handle, in_word: bool) -> Result<Vec<Element>> {
+    let nodes = handle.children.borrow();
+    nodes
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| tag_name(c).is_some())
+        .map(|(i, c)| {
+            Ok(Element {
+                tail: non_blank_text(nodes.get(i + 1)),
+                ..element_from_handle(c, in_word)?
+            })
+        })
+        .collect()
+}
+
+impl HOCR {
+    /// Create a new [`HOCR`] instance from a string containing HTML5 hOCR.
+    ///
+    /// Unlike [`HOCR::from_str`], this accepts markup that is not well-formed XML, such as
+    /// unclosed `<meta>` tags and unquoted attribute values, using an HTML5 parser.
+    pub fn from_html5(html: &str) -> Result<Self> {
+        let dom = parse_document(RcDom::default(), ParseOpts::default()).one(html);
+        let root = find_child(&dom.document, "html").ok_or(HOCRParserError::NoHeadElement)?;
+        let head = find_child(&root, "head").ok_or(HOCRParserError::NoHeadElement)?;
+
+        let meta_tags: Vec<_> = child_elements(&head)
+            .iter()
+            .filter(|e| tag_name(e) == Some("meta"))
+            .filter_map(|e| {
+                let attributes = attributes(e);
+                let attribute = |name: &str| {
+                    attributes
+                        .iter()
+                        .find(|(n, _)| n == name)
+                        .map(|(_, v)| v.clone())
+                };
+                Some((attribute("name")?, attribute("content")?))
+            })
+            .collect();
+        let metadata = DocumentMetadata::from_meta_tags(&meta_tags)?;
+
+        let body = find_child(&root, "body").ok_or(HOCRParserError::NoBodyElement)?;
+        let elements = elements_from_children(&body, false)?;
+
+        Ok(HOCR::from_parts(metadata, elements))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_tag_soup() {
+        let html = r#"<!DOCTYPE html>
+            <html><head>
+            <meta name=ocr-system content=test>
+            <meta name=ocr-capabilities content="ocr_page ocrx_word">
+            <body>
+            <div class=ocr_page id=page_1 title="bbox 0 0 100 100">
+                <span class=ocrx_word id=w1 title="bbox 0 0 10 10">caf&eacute;</span>
+                <span class=ocrx_word id=w2>Hel<em>lo</em>!</span>
+            </div>"#;
+        let hocr = HOCR::from_html5(html).unwrap();
+
+        assert_eq!(hocr.system, "test");
+        assert_eq!(hocr.capabilities, ["ocr_page", "ocrx_word"]);
+        let word = &hocr.elements[0].children[0];
+        assert_eq!(word.id.as_deref(), Some("w1"));
+        assert_eq!(word.text.as_deref(), Some("café"));
+        assert_eq!(hocr.elements[0].children[1].plain_text(), "Hello!");
+    }
+
+    #[test]
+    fn html5_matches_xml_parser() {
+        let xml = std::fs::read_to_string("examples/data/paper-image.hocr").unwrap();
+        assert_eq!(
+            HOCR::from_html5(&xml).unwrap(),
+            HOCR::from_str(&xml).unwrap()
+        );
+    }
+
+    #[test]
+    fn html5_unknown_element() {
+        let html =
+            "<meta name=ocr-system content=test><meta name=ocr-capabilities content=ocr_page>\
+            <body><div class=ocr_page><b>bold</b></div>";
+        assert!(matches!(
+            HOCR::from_html5(html),
+            Err(HOCRParserError::UnknownElement(ErrorLocation::Name(_)))
+        ));
+    }
+}
@@ -1,90 +1,87 @@
-use crate::{element::{Element, ElementBorrowed}, hocr::{HOCRBorrowed, HOCR}};
+use crate::{element::{Element, ElementBorrowed}, hocr::{HOCRBorrowed, HOCR}, parsing::is_formatting_element, traits::{HocrDocument, HocrElement}};
 
 
-pub struct ElementsIterator<'a> {
-    elements: Vec<&'a Element>,
+pub struct ElementsIterator<'a, E = Element> {
+    elements: Vec<&'a E>,
 }
 
-impl<'a> Iterator for ElementsIterator<'a> {
-    type Item = &'a Element;
+impl<'a, E> Iterator for ElementsIterator<'a, E> {
+    type Item = &'a E;
 
     fn next(&mut self) -> Option<Self::Item> {
         self.elements.pop()
     }
 }
 
-pub struct ElementsBorrowedIterator<'a> {
-    elements: Vec<&'a ElementBorrowed<'a>>,
+pub type ElementsBorrowedIterator<'a> = ElementsIterator<'a, ElementBorrowed<'a>>;
+
+/// Formatting elements such as `<strong>` are part of the text of their word rather than hOCR
+/// elements, so the iterators skip them, though not their descendants.
+fn is_hocr_element<E: HocrElement>(e: &&E) -> bool {
+    !is_formatting_element(e.element_type(), e.tag_name())
 }
 
-impl<'a> Iterator for ElementsBorrowedIterator<'a> {
-    type Item = &'a ElementBorrowed<'a>;
+pub(crate) fn breadth_first<E: HocrElement>(roots: &[E]) -> ElementsIterator<'_, E> {
+    let mut elements: Vec<&E> = roots.iter().collect();
+    let mut index = 0;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.elements.pop()
+    while let Some(element) = elements.get(index) {
+        elements.extend(element.children().iter());
+        index += 1;
+    }
+
+    elements.retain(is_hocr_element);
+    ElementsIterator { elements }
+}
+
+pub(crate) fn document_order<E: HocrElement>(roots: &[E]) -> ElementsIterator<'_, E> {
+    let mut elements = vec![];
+    let mut stack: Vec<&E> = roots.iter().rev().collect();
+
+    while let Some(element) = stack.pop() {
+        elements.push(element);
+        stack.extend(element.children().iter().rev());
     }
+
+    elements.retain(is_hocr_element);
+    elements.reverse();
+    ElementsIterator { elements }
 }
 
 impl HOCR {
     /// Returns an iterator over all elements in the hOCR document.
     /// Note that the iterator returns elements in a breadth-first order.
-    pub fn iter(&self) -> ElementsIterator {
-        let mut elements: Vec<&Element> = self.elements.iter().collect();
-        let mut index = 0;
-        
-        while let Some(element) = elements.get(index) {
-            elements.extend(element.children.iter());
-            index += 1;
-        }
-        
-        ElementsIterator { elements }
+    pub fn iter(&self) -> ElementsIterator<'_> {
+        HocrDocument::iter(self)
+    }
+
+    /// Returns an iterator over all elements in the hOCR document in document order,
+    /// i.e. a depth-first pre-order traversal.
+    pub fn iter_document_order(&self) -> ElementsIterator<'_> {
+        HocrDocument::iter_document_order(self)
     }
 }
 
 impl Element {
     /// Returns an iterator over all descendants of this hOCR element.
     /// Note that the iterator returns elements in a breadth-first order.
-    pub fn descendants(&self) -> ElementsIterator {
-        let mut elements: Vec<&Element> = self.children.iter().collect();
-        let mut index = 0;
-        
-        while let Some(element) = elements.get(index) {
-            elements.extend(element.children.iter());
-            index += 1;
-        }
-        
-        ElementsIterator { elements }
+    pub fn descendants(&self) -> ElementsIterator<'_> {
+        HocrElement::descendants(self)
     }
 }
 
 impl<'a> HOCRBorrowed<'a> {
     /// Returns an iterator over all elements in the hOCR document.
     /// Note that the iterator returns elements in a breadth-first order.
-    pub fn iter(&self) -> ElementsBorrowedIterator {
-        let mut elements: Vec<&ElementBorrowed> = self.elements.iter().collect();
-        let mut index = 0;
-        
-        while let Some(element) = elements.get(index) {
-            elements.extend(element.children.iter());
-            index += 1;
-        }
-        
-        ElementsBorrowedIterator { elements }
+    pub fn iter(&self) -> ElementsBorrowedIterator<'_> {
+        HocrDocument::iter(self)
     }
 }
 
 impl<'a> ElementBorrowed<'a> {
     /// Returns an iterator over all descendants of this hOCR element.
     /// Note that the iterator returns elements in a breadth-first order.
-    pub fn descendants(&self) -> ElementsBorrowedIterator {
-        let mut elements: Vec<&ElementBorrowed> = self.children.iter().collect();
-        let mut index = 0;
-        
-        while let Some(element) = elements.get(index) {
-            elements.extend(element.children.iter());
-            index += 1;
-        }
-        
-        ElementsBorrowedIterator { elements }
+    pub fn descendants(&self) -> ElementsBorrowedIterator<'_> {
+        HocrElement::descendants(self)
     }
-}
\ No newline at end of file
+}
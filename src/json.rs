@@ -0,0 +1,530 @@
+//! The layout used by the `serde` implementations of [`HOCR`] and [`Element`].
+//!
+//! A document is an object with the following fields:
+//!
+//! - `version`: the layout version, currently [`FORMAT_VERSION`]
+//! - `system`, `capabilities`, `number_of_pages`, `langs`, `scripts`: the `ocr-*` metadata
+//! - `metadata`: all other `<meta>` tags as `{"name": ..., "content": ...}` objects
+//! - `elements`: the top-level hOCR elements
+//!
+//! An element is an object with the fields `class`, `tag`, `id`, `lang`, `attributes`, `text`,
+//! `tail`, `properties` and `children`. Properties are kept in `title` order; typed properties are
+//! written as `{"name": "bbox", "value": {"x0": 0, "y0": 0, "x1": 10, "y1": 10}}` and all
+//! others as `{"name": "x_size", "values": ["21.25"]}` (see [`Property`]).
+//!
+//! Optional fields are omitted when empty. Converting hOCR to JSON and back, then writing it
+//! with [`HOCR::to_xhtml`], yields a document that parses to the same [`HOCR`].
+//!
+//! With the `json-schema` feature, [`schema`] returns a JSON Schema of this layout; a copy is
+//! kept in `schema/hocr.v1.schema.json` in the repository.
+//!
+//! [`in_units`] writes the layout with lengths in physical units instead of pixels, for output
+//! only: such a document cannot be read back as hOCR.
+//!
+//! [`FORMAT_VERSION`]: crate::json::FORMAT_VERSION
+//! [`schema`]: crate::json::schema
+//! [`in_units`]: crate::json::in_units
+
+use crate::{
+    element::{Element, ElementBorrowed},
+    geometry::BBox,
+    hocr::{HOCRBorrowed, HOCR},
+    property::Property,
+    spec_definitions::{elements::OCR_PAGE, properties},
+    units::{Unit, Units},
+};
+
+/// Version of the JSON layout written by this crate.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// JSON representation of an hOCR document.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "json-schema", schemars(title = "hOCR document"))]
+pub struct JsonDocument {
+    /// Version of the JSON layout.
+    pub version: u32,
+    /// Content of the `ocr-system` metadata.
+    pub system: String,
+    /// Content of the `ocr-capabilities` metadata.
+    pub capabilities: Vec<String>,
+    /// Content of the `ocr-number-of-pages` metadata.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub number_of_pages: Option<u32>,
+    /// Content of the `ocr-langs` metadata.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub langs: Option<Vec<String>>,
+    /// Content of the `ocr-scripts` metadata.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scripts: Option<Vec<String>>,
+    /// All other `<meta>` tags, in document order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub metadata: Vec<JsonMeta>,
+    pub elements: Vec<JsonElement>,
+}
+
+/// A `<meta>` tag of the document head.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct JsonMeta {
+    pub name: String,
+    pub content: String,
+}
+
+/// An attribute of an element other than `class`, `id`, `title` and `lang`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct JsonAttribute {
+    pub name: String,
+    pub value: String,
+}
+
+/// JSON representation of an hOCR element.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct JsonElement {
+    /// The hOCR element class, e.g. `ocr_line`.
+    pub class: String,
+    /// The XHTML tag name, e.g. `span`.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub tag: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attributes: Vec<JsonAttribute>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// Text following the element, before its next sibling.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tail: Option<String>,
+    /// Properties from the `title` attribute, in order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(
+        feature = "json-schema",
+        schemars(with = "Vec<crate::property::repr::PropertyRepr>")
+    )]
+    pub properties: Vec<Property>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<JsonElement>,
+}
+
+impl From<Element> for JsonElement {
+    fn from(e: Element) -> Self {
+        Self {
+            properties: e.typed_properties(),
+            class: e.element_type,
+            tag: e.tag_name,
+            id: e.id,
+            lang: e.lang,
+            attributes: e
+                .attributes
+                .into_iter()
+                .map(|(name, value)| JsonAttribute { name, value })
+                .collect(),
+            text: e.text,
+            tail: e.tail,
+            children: e.children.into_iter().map(JsonElement::from).collect(),
+        }
+    }
+}
+
+impl From<JsonElement> for Element {
+    fn from(e: JsonElement) -> Self {
+        Self {
+            element_type: e.class,
+            id: e.id,
+            tag_name: e.tag,
+            properties: e.properties.iter().map(Property::to_raw).collect(),
+            lang: e.lang,
+            attributes: e
+                .attributes
+                .into_iter()
+                .map(|a| (a.name, a.value))
+                .collect(),
+            text: e.text,
+            tail: e.tail,
+            children: e.children.into_iter().map(Element::from).collect(),
+        }
+    }
+}
+
+impl From<HOCR> for JsonDocument {
+    fn from(hocr: HOCR) -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            system: hocr.system,
+            capabilities: hocr.capabilities,
+            number_of_pages: hocr.number_of_pages,
+            langs: hocr.langs,
+            scripts: hocr.scripts,
+            metadata: hocr
+                .metadata
+                .into_iter()
+                .map(|(name, content)| JsonMeta { name, content })
+                .collect(),
+            elements: hocr.elements.into_iter().map(JsonElement::from).collect(),
+        }
+    }
+}
+
+impl TryFrom<JsonDocument> for HOCR {
+    type Error = String;
+
+    fn try_from(doc: JsonDocument) -> Result<Self, Self::Error> {
+        if doc.version > FORMAT_VERSION {
+            return Err(format!(
+                "unsupported hOCR JSON version {} (newest supported is {FORMAT_VERSION})",
+                doc.version
+            ));
+        }
+
+        Ok(Self {
+            system: doc.system,
+            capabilities: doc.capabilities,
+            number_of_pages: doc.number_of_pages,
+            langs: doc.langs,
+            scripts: doc.scripts,
+            metadata: doc
+                .metadata
+                .into_iter()
+                .map(|m| (m.name, m.content))
+                .collect(),
+            elements: doc.elements.into_iter().map(Element::from).collect(),
+        })
+    }
+}
+
+impl serde::Serialize for ElementBorrowed<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        JsonElement::from(Element::from_element_borrowed(self)).serialize(serializer)
+    }
+}
+
+impl serde::Serialize for HOCRBorrowed<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        JsonDocument::from(HOCR::from_hocr_borrowed(self.clone())).serialize(serializer)
+    }
+}
+
+/// The JSON layout of a document with lengths converted to another unit, see [`in_units`].
+#[derive(Debug, Clone, Copy)]
+pub struct JsonInUnits<'a> {
+    hocr: &'a HOCR,
+    unit: Unit,
+    default: Units,
+}
+
+/// Returns the JSON layout of `hocr` with the `bbox`, `x_bboxes`, `poly` and `x_fsize`
+/// properties converted from pixels (and points) to `unit` and rounded to hundredths, for
+/// serializing. Lengths are converted with the `scan_res` of each page, or with `default` for
+/// pages without one. The document gets a `units` field with the [symbol](Unit::symbol) of
+/// `unit`.
+pub fn in_units(hocr: &HOCR, unit: Unit, default: Units) -> JsonInUnits<'_> {
+    JsonInUnits {
+        hocr,
+        unit,
+        default,
+    }
+}
+
+/// A [`JsonDocument`] with a `units` field and elements in [`JsonInUnits::unit`].
+#[derive(serde::Serialize)]
+struct DocumentInUnits {
+    version: u32,
+    units: &'static str,
+    system: String,
+    capabilities: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    number_of_pages: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    langs: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scripts: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    metadata: Vec<JsonMeta>,
+    elements: Vec<ElementInUnits>,
+}
+
+/// A [`JsonElement`] with converted properties; `element` has no properties and children.
+#[derive(serde::Serialize)]
+struct ElementInUnits {
+    #[serde(flatten)]
+    element: JsonElement,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    properties: Vec<PropertyInUnits>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    children: Vec<ElementInUnits>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(untagged)]
+enum PropertyInUnits {
+    Converted(ConvertedProperty),
+    Other(Property),
+}
+
+#[derive(serde::Serialize)]
+#[serde(tag = "name", content = "value", rename_all = "snake_case")]
+enum ConvertedProperty {
+    #[serde(rename = "bbox")]
+    BBox(BoxInUnits),
+    #[serde(rename = "x_bboxes")]
+    XBBoxes(Vec<BoxInUnits>),
+    Poly(Vec<(f64, f64)>),
+    XFsize(f64),
+}
+
+#[derive(serde::Serialize)]
+struct BoxInUnits {
+    x0: f64,
+    y0: f64,
+    x1: f64,
+    y1: f64,
+}
+
+/// Round a converted length to hundredths.
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+impl JsonInUnits<'_> {
+    /// The unit lengths are converted to.
+    pub fn unit(&self) -> Unit {
+        self.unit
+    }
+
+    fn x(&self, units: &Units, px: f64) -> f64 {
+        round(units.convert_x(px, Unit::Pixel, self.unit))
+    }
+
+    fn y(&self, units: &Units, px: f64) -> f64 {
+        round(units.convert_y(px, Unit::Pixel, self.unit))
+    }
+
+    fn bbox(&self, units: &Units, b: &BBox) -> BoxInUnits {
+        BoxInUnits {
+            x0: self.x(units, b.x0 as f64),
+            y0: self.y(units, b.y0 as f64),
+            x1: self.x(units, b.x1 as f64),
+            y1: self.y(units, b.y1 as f64),
+        }
+    }
+
+    fn property(&self, units: &Units, p: Property) -> PropertyInUnits {
+        use ConvertedProperty as C;
+
+        let converted = match p {
+            Property::BBox(b) => C::BBox(self.bbox(units, &b)),
+            Property::XBBoxes(boxes) => {
+                C::XBBoxes(boxes.iter().map(|b| self.bbox(units, b)).collect())
+            }
+            Property::Poly(p) => C::Poly(
+                p.points
+                    .iter()
+                    .map(|&(x, y)| (self.x(units, x as f64), self.y(units, y as f64)))
+                    .collect(),
+            ),
+            Property::XFsize(size) => C::XFsize(round(units.font_size(size, self.unit))),
+            Property::Other { name, mut values } => {
+                // values that are not kept typed, e.g. `x_fsize 12.0`, are converted one by one
+                let convert = |i: usize, v: f64| match name.as_str() {
+                    properties::X_FSIZE => Some(round(units.font_size(v, self.unit))),
+                    properties::BBOX | properties::X_BBOXES | properties::POLY if i % 2 == 0 => {
+                        Some(self.x(units, v))
+                    }
+                    properties::BBOX | properties::X_BBOXES | properties::POLY => {
+                        Some(self.y(units, v))
+                    }
+                    _ => None,
+                };
+                for (i, value) in values.iter_mut().enumerate() {
+                    if let Some(v) = value.parse().ok().and_then(|v| convert(i, v)) {
+                        *value = v.to_string();
+                    }
+                }
+                return PropertyInUnits::Other(Property::Other { name, values });
+            }
+            p => return PropertyInUnits::Other(p),
+        };
+        PropertyInUnits::Converted(converted)
+    }
+
+    fn element(&self, mut units: Units, e: &Element) -> ElementInUnits {
+        if e.element_type == OCR_PAGE {
+            units = Units::of_page(e, self.default);
+        }
+
+        let element = JsonElement {
+            class: e.element_type.clone(),
+            tag: e.tag_name.clone(),
+            id: e.id.clone(),
+            lang: e.lang.clone(),
+            attributes: e
+                .attributes
+                .iter()
+                .map(|(name, value)| JsonAttribute {
+                    name: name.clone(),
+                    value: value.clone(),
+                })
+                .collect(),
+            text: e.text.clone(),
+            tail: e.tail.clone(),
+            properties: vec![],
+            children: vec![],
+        };
+        ElementInUnits {
+            element,
+            properties: e
+                .typed_properties()
+                .into_iter()
+                .map(|p| self.property(&units, p))
+                .collect(),
+            children: e.children.iter().map(|c| self.element(units, c)).collect(),
+        }
+    }
+}
+
+impl serde::Serialize for JsonInUnits<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let hocr = self.hocr;
+        DocumentInUnits {
+            version: FORMAT_VERSION,
+            units: self.unit.symbol(),
+            system: hocr.system.clone(),
+            capabilities: hocr.capabilities.clone(),
+            number_of_pages: hocr.number_of_pages,
+            langs: hocr.langs.clone(),
+            scripts: hocr.scripts.clone(),
+            metadata: hocr
+                .metadata
+                .iter()
+                .map(|(name, content)| JsonMeta {
+                    name: name.clone(),
+                    content: content.clone(),
+                })
+                .collect(),
+            elements: hocr
+                .elements
+                .iter()
+                .map(|e| self.element(self.default, e))
+                .collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Returns the JSON Schema of the layout described in the [module documentation](self).
+#[cfg(feature = "json-schema")]
+pub fn schema() -> schemars::Schema {
+    schemars::schema_for!(JsonDocument)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{document, document_with_meta};
+
+    #[test]
+    fn json_round_trip_example() {
+        let xml = std::fs::read_to_string("examples/data/paper-image.hocr").unwrap();
+        let hocr = HOCR::from_str(&xml).unwrap();
+
+        let json = serde_json::to_string(&hocr).unwrap();
+        let from_json: HOCR = serde_json::from_str(&json).unwrap();
+        assert_eq!(from_json, hocr);
+
+        let reparsed = HOCR::from_str(&from_json.to_xhtml()).unwrap();
+        assert_eq!(reparsed, hocr);
+    }
+
+    #[test]
+    fn json_round_trip_mixed_content() {
+        let xml = document(
+            "ocr_par ocr_line",
+            r#"<p class="ocr_par" id="a">Intro <span class="ocr_line" id="l">line</span> tail</p>"#,
+        );
+        let hocr = HOCR::from_str(&xml).unwrap();
+        let par = &hocr.elements[0];
+        assert_eq!(par.text.as_deref(), Some("Intro "));
+        assert_eq!(par.children[0].tail.as_deref(), Some(" tail"));
+
+        let from_json: HOCR = serde_json::from_str(&serde_json::to_string(&hocr).unwrap()).unwrap();
+        let reparsed = HOCR::from_str(&from_json.to_xhtml()).unwrap();
+        assert_eq!(reparsed, hocr);
+    }
+
+    #[test]
+    fn json_layout() {
+        let xml = document_with_meta(
+            "ocrx_word",
+            r#"<meta name="ocr-scanner" content="flatbed"/>"#,
+            r#"<span class="ocrx_word" id="w1" dir="rtl" title="bbox 0 0 10 10; x_wconf 95; x_size 21.25">a</span>"#,
+        );
+        let hocr = HOCR::from_str(&xml).unwrap();
+        let json = serde_json::to_value(&hocr).unwrap();
+
+        assert_eq!(json["version"], FORMAT_VERSION);
+        assert_eq!(json["metadata"][0]["name"], "ocr-scanner");
+        let word = &json["elements"][0];
+        assert_eq!(word["tag"], "span");
+        assert_eq!(word["attributes"][0]["name"], "dir");
+        assert_eq!(word["properties"][0]["value"]["x1"], 10);
+        assert_eq!(word["properties"][1]["value"], 95.0);
+        assert_eq!(word["properties"][2]["values"][0], "21.25");
+
+        let back: HOCR = serde_json::from_value(json).unwrap();
+        assert_eq!(back, hocr);
+    }
+
+    #[test]
+    fn json_in_units() {
+        let xml = document(
+            "ocr_page ocrx_word",
+            r#"<div class="ocr_page" id="page_1" title="bbox 0 0 144 288; scan_res 72 72">
+                <span class="ocrx_word" id="w1" lang="deu" title="bbox 0 0 36 72; poly 0 0 36 0 36 72; x_bboxes 0 0 18 72 18 0 36 72; x_fsize 12.0; x_wconf 90">ab</span>
+            </div>
+            <div class="ocr_page" id="page_2" title="bbox 0 0 300 150 2"></div>"#,
+        );
+        let hocr = HOCR::from_str(&xml).unwrap();
+        let default = Units::with_dpi(150.0).unwrap();
+        let json = serde_json::to_value(in_units(&hocr, Unit::Inch, default)).unwrap();
+
+        assert_eq!(json["units"], "in");
+        let page = &json["elements"][0];
+        assert_eq!(page["properties"][0]["value"]["y1"], 4.0);
+        let word = &page["children"][0];
+        assert_eq!(word["lang"], "deu");
+        assert_eq!(word["text"], "ab");
+        assert_eq!(word["properties"][0]["value"]["x1"], 0.5);
+        assert_eq!(word["properties"][1]["value"][2], serde_json::json!([0.5, 1.0]));
+        assert_eq!(word["properties"][2]["value"][1]["x0"], 0.25);
+        // `x_fsize 12.0` is not kept typed
+        assert_eq!(word["properties"][3]["values"][0], "0.17");
+        assert_eq!(word["properties"][4]["value"], 90.0);
+        // a malformed box is converted value by value, with the default resolution
+        assert_eq!(
+            json["elements"][1]["properties"][0]["values"],
+            serde_json::json!(["0", "0", "2", "1", "0.01"])
+        );
+    }
+
+    #[test]
+    fn reject_newer_version() {
+        let json = r#"{"version": 999, "system": "test", "capabilities": [], "elements": []}"#;
+        assert!(serde_json::from_str::<HOCR>(json).is_err());
+    }
+
+    #[cfg(feature = "json-schema")]
+    #[test]
+    fn schema_file_is_up_to_date() {
+        let expected = serde_json::to_string_pretty(&schema()).unwrap() + "\n";
+        let actual = std::fs::read_to_string("schema/hocr.v1.schema.json").unwrap();
+        assert!(
+            actual == expected,
+            "schema/hocr.v1.schema.json is outdated; regenerate it with \
+             `cargo run --example json_schema --features json-schema > schema/hocr.v1.schema.json`"
+        );
+    }
+}
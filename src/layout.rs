@@ -0,0 +1,14 @@
+//! - [`reading_order`]: infer the reading order of blocks from their boxes
+//! - [`deskew`]: rotate skewed or rotated pages to upright coordinates and back
+//! - [`lines`]: group words that are not in lines into `ocr_line` elements
+//! - [`structure`]: classify paragraphs as titles, headings and captions
+//!
+//! [`reading_order`]: crate::layout::reading_order
+//! [`deskew`]: crate::layout::deskew
+//! [`lines`]: crate::layout::lines
+//! [`structure`]: crate::layout::structure
+
+pub mod deskew;
+pub mod lines;
+pub mod reading_order;
+pub mod structure;
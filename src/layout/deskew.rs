@@ -0,0 +1,388 @@
+//! Normalization of skewed and rotated pages to upright coordinates.
+//!
+//! The `textangle` property gives the angle in degrees by which text is rotated
+//! counterclockwise. [`deskew_page`] rotates all geometric properties of a page by its text
+//! angle about the center of the page, so that the text becomes horizontal and the page box
+//! keeps its top-left corner. It returns the [`Transform`] it applied; [`restore_page`] applies
+//! the inverse to map upright coordinates back to the original image.
+//!
+//! Boxes are axis-aligned, so a box rotated by an angle other than a multiple of 90° is replaced
+//! by the larger box enclosing the rotated one, and the rotated box itself is recorded as the
+//! element's `poly` unless it has one. [`restore_page`] rebuilds every box from its restored
+//! `poly` and removes the recorded polygons again, so the boxes of a restored page are exactly
+//! the original ones. Character boxes in `x_bboxes` have no such record and are only restored
+//! exactly for quarter turns, the points of other polygons are rounded to whole pixels and
+//! baselines as Tesseract writes them, and `cuts` are replaced by the `x_bboxes` they describe.
+//!
+//! ```
+//! use hocr_parser::{layout::deskew, HOCR};
+//!
+//! let xml = std::fs::read_to_string("examples/data/paper-image.hocr").unwrap();
+//! let mut hocr = HOCR::from_str(&xml).unwrap();
+//! let transforms = deskew::deskew(&mut hocr);
+//! for (page, t) in hocr.elements.iter_mut().zip(&transforms) {
+//!     if let Some(t) = t {
+//!         deskew::restore_page(page, t);
+//!     }
+//! }
+//! ```
+
+use crate::{
+    baseline::Baseline,
+    element::Element,
+    geometry::{BBox, Transform},
+    glyph::glyph_boxes,
+    hocr::HOCR,
+    polygon::Polygon,
+    property::Property,
+    recompute::children_bbox,
+    spec_definitions::{elements::OCR_PAGE, properties},
+};
+
+/// Angles closer to zero than this, in degrees, are not corrected.
+const MIN_ANGLE: f64 = 0.01;
+
+fn text_angle(e: &Element) -> Option<f64> {
+    e.property(properties::TEXTANGLE)?.first()?.parse().ok()
+}
+
+/// The angle of the text on a page in degrees, counterclockwise: the `textangle` of the page
+/// itself, or else the median `textangle` of its descendants.
+pub fn page_angle(page: &Element) -> Option<f64> {
+    if let Some(angle) = text_angle(page) {
+        return Some(angle);
+    }
+
+    fn collect(e: &Element, angles: &mut Vec<f64>) {
+        for child in &e.children {
+            angles.extend(text_angle(child));
+            collect(child, angles);
+        }
+    }
+    let mut angles = vec![];
+    collect(page, &mut angles);
+    angles.sort_by(f64::total_cmp);
+    angles.get(angles.len() / 2).copied()
+}
+
+/// The clockwise rotation of `t` in degrees.
+fn rotation(t: &Transform) -> f64 {
+    t.b.atan2(t.a).to_degrees()
+}
+
+/// An angle in degrees in the range (-180, 180], with rounding errors removed.
+fn normalize_angle(degrees: f64) -> f64 {
+    let mut a = (degrees * 1e6).round() / 1e6 % 360.0;
+    if a > 180.0 {
+        a -= 360.0;
+    } else if a <= -180.0 {
+        a += 360.0;
+    }
+    a + 0.0
+}
+
+/// Map the geometric properties of `e` itself by `t`, giving it the box `bbox`: `x_bboxes` and
+/// `poly` are mapped point by point, `baseline` is kept relative to `bbox` and `textangle` is
+/// reduced by the rotation of `t`. `cuts` are relative to the box and cannot be mapped, so they
+/// are replaced by the character boxes they describe.
+fn transform_properties(e: &mut Element, t: &Transform, bbox: Option<BBox>) {
+    let old_bbox = e.bbox();
+    if let Some(cuts) = e.remove_property(properties::CUTS) {
+        let boxes = glyph_boxes(old_bbox, None::<&[String]>, Some(&cuts));
+        if e.property(properties::X_BBOXES).is_none() && !boxes.is_empty() {
+            e.set_property(properties::X_BBOXES, Property::XBBoxes(boxes).values());
+        }
+    }
+
+    for (name, values) in &mut e.properties {
+        let mapped = match Property::parse(name, values) {
+            Property::BBox(_) => match bbox {
+                Some(b) => Property::BBox(b),
+                None => continue,
+            },
+            Property::XBBoxes(boxes) => {
+                Property::XBBoxes(boxes.iter().map(|b| t.apply_bbox(b)).collect())
+            }
+            Property::Poly(p) => Property::Poly(p.transform(t)),
+            Property::TextAngle(angle) => Property::TextAngle(normalize_angle(angle - rotation(t))),
+            _ => continue,
+        };
+        *values = mapped.values();
+    }
+
+    if let (Some(old_bbox), Some(bbox), Some(baseline)) = (old_bbox, bbox, e.baseline()) {
+        let [start, end] = baseline.polyline(&old_bbox).map(|(x, y)| t.apply(x, y));
+        match Baseline::through(&bbox, start, end) {
+            Some(b) => e.set_property(
+                properties::BASELINE,
+                Property::Baseline(b.rounded()).values(),
+            ),
+            None => {
+                e.remove_property(properties::BASELINE);
+            }
+        }
+    }
+}
+
+/// Map all geometric properties of `e` and its descendants by `t`: `bbox`, `x_bboxes` and
+/// `poly` are mapped point by point, `baseline` is kept relative to the mapped `bbox` and
+/// `textangle` is reduced by the rotation of `t`.
+///
+/// A baseline that becomes vertical cannot be expressed by the `baseline` property and is
+/// removed. `cuts` are replaced by the `x_bboxes` they describe, which are then mapped.
+pub fn transform_element(e: &mut Element, t: &Transform) {
+    let bbox = e.bbox().map(|b| t.apply_bbox(&b));
+    transform_properties(e, t, bbox);
+    for child in &mut e.children {
+        transform_element(child, t);
+    }
+}
+
+/// Whether `t` rotates by a multiple of 90°, so that it maps boxes to boxes exactly.
+fn is_quarter_turn(t: &Transform) -> bool {
+    normalize_angle(rotation(t)) % 90.0 == 0.0
+}
+
+/// The box `b` mapped by `t` as a polygon, with its corners rounded to whole pixels such that
+/// mapping the polygon back by `inverse` gives a polygon with the box `b` again.
+fn rotated_box(b: &BBox, t: &Transform, inverse: &Transform) -> Polygon {
+    // the four whole-pixel points around each mapped corner, nearest first
+    let candidates: Vec<[(i32, i32); 4]> = Polygon::from_bbox(b)
+        .points
+        .iter()
+        .map(|&(x, y)| {
+            let (x, y) = t.apply(x as f64, y as f64);
+            let mut points = [
+                (x.floor(), y.floor()),
+                (x.ceil(), y.floor()),
+                (x.floor(), y.ceil()),
+                (x.ceil(), y.ceil()),
+            ];
+            points.sort_by(|p, q| {
+                let distance = |(px, py): (f64, f64)| (px - x).powi(2) + (py - y).powi(2);
+                distance(*p).total_cmp(&distance(*q))
+            });
+            points.map(|(x, y)| (x as i32, y as i32))
+        })
+        .collect();
+
+    (0..1 << (2 * candidates.len()))
+        .map(|choice: usize| {
+            let points = candidates
+                .iter()
+                .enumerate()
+                .map(|(i, c)| c[(choice >> (2 * i)) & 3])
+                .collect();
+            Polygon::new(points)
+        })
+        .find(|p| p.transform(inverse).bbox() == Some(*b))
+        .unwrap_or_else(|| Polygon::from_bbox(b).transform(t))
+}
+
+/// Map `e` and its descendants by `t` as [`transform_element`] does, recording the rotated box
+/// of every element without a `poly` as its `poly`.
+fn deskew_element(e: &mut Element, t: &Transform, inverse: &Transform) {
+    let recorded = match e.poly() {
+        Some(_) => None,
+        None => e.bbox().map(|b| rotated_box(&b, t, inverse)),
+    };
+    let bbox = e.bbox().map(|b| t.apply_bbox(&b));
+    transform_properties(e, t, bbox);
+    if let Some(poly) = recorded {
+        e.set_property(properties::POLY, poly.values());
+    }
+    for child in &mut e.children {
+        deskew_element(child, t, inverse);
+    }
+}
+
+/// Map `e` and its descendants back by `inverse`, the inverse of `t`, rebuilding every box
+/// from the restored `poly` and removing the polygons recorded by [`deskew_element`].
+fn restore_element(e: &mut Element, t: &Transform, inverse: &Transform) {
+    let deskewed = e.poly();
+    let restored = deskewed.as_ref().map(|p| p.transform(inverse));
+    let bbox = match restored.as_ref().and_then(Polygon::bbox) {
+        Some(b) if e.bbox().is_some() => Some(b),
+        _ => e.bbox().map(|b| inverse.apply_bbox(&b)),
+    };
+    transform_properties(e, inverse, bbox);
+    if let Some(b) = bbox {
+        if deskewed == Some(rotated_box(&b, t, inverse)) {
+            e.remove_property(properties::POLY);
+        }
+    }
+    for child in &mut e.children {
+        restore_element(child, t, inverse);
+    }
+}
+
+/// The transform rotating `page` clockwise by `angle` degrees about its center, so that text
+/// rotated counterclockwise by `angle` becomes upright, and moving the rotated page box back to
+/// the page's top-left corner.
+pub fn upright_transform(page: &BBox, angle: f64) -> Transform {
+    let (cx, cy) = (
+        (page.x0 + page.x1) as f64 / 2.0,
+        (page.y0 + page.y1) as f64 / 2.0,
+    );
+    let rotate = Transform::rotate_about(angle, cx, cy);
+    let rotated = rotate.apply_bbox(page);
+    rotate.then(&Transform::translate(
+        (page.x0 - rotated.x0) as f64,
+        (page.y0 - rotated.y0) as f64,
+    ))
+}
+
+/// Rotate the geometry of `page` to upright coordinates.
+///
+/// The angle is taken from `angle` if given, and otherwise from [`page_angle`]. Returns the
+/// applied transform, or `None` if the page is already upright or has no box.
+pub fn deskew_page(page: &mut Element, angle: Option<f64>) -> Option<Transform> {
+    let angle = normalize_angle(angle.or_else(|| page_angle(page))?);
+    if angle.abs() < MIN_ANGLE {
+        return None;
+    }
+
+    let bbox = page.bbox().or_else(|| children_bbox(page))?;
+    let t = upright_transform(&bbox, angle);
+    match t.inverse() {
+        Some(inverse) if !is_quarter_turn(&t) => deskew_element(page, &t, &inverse),
+        _ => transform_element(page, &t),
+    }
+    Some(t)
+}
+
+/// Map the geometry of a page deskewed by [`deskew_page`] back to the original image, given
+/// the transform it returned.
+///
+/// Boxes are rebuilt from the polygons recorded when deskewing, so they are restored exactly,
+/// see the [module documentation](self).
+pub fn restore_page(page: &mut Element, t: &Transform) {
+    let Some(inverse) = t.inverse() else {
+        return;
+    };
+    if is_quarter_turn(t) {
+        transform_element(page, &inverse);
+    } else {
+        restore_element(page, t, &inverse);
+    }
+}
+
+/// Deskew every page of the document by its [`page_angle`], returning the applied transform of
+/// each top-level element.
+pub fn deskew(hocr: &mut HOCR) -> Vec<Option<Transform>> {
+    hocr.elements
+        .iter_mut()
+        .map(|e| {
+            if e.element_type == OCR_PAGE {
+                deskew_page(e, None)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::document;
+
+    fn rotated_page() -> Element {
+        // a page scanned upside down
+        let xml = document(
+            "ocr_page ocr_line ocrx_word",
+            r#"<div class="ocr_page" id="page_1" title="bbox 0 0 600 400">
+                <span class="ocr_line" id="line_1" title="bbox 100 300 500 350; baseline 0 -10; textangle 180">
+                    <span class="ocrx_word" id="word_1" title="bbox 300 300 500 350; x_bboxes 300 300 400 350 400 300 500 350">ab</span>
+                </span>
+            </div>"#,
+        );
+        HOCR::from_str(&xml).unwrap().elements.remove(0)
+    }
+
+    #[test]
+    fn deskew_upside_down_page() {
+        let mut page = rotated_page();
+        assert_eq!(page_angle(&page), Some(180.0));
+
+        let t = deskew_page(&mut page, None).unwrap();
+        let line = &page.children[0];
+        assert_eq!(page.bbox(), Some(BBox::new(0, 0, 600, 400)));
+        assert_eq!(line.bbox(), Some(BBox::new(100, 50, 500, 100)));
+        assert_eq!(line.property(properties::TEXTANGLE).unwrap(), ["0"]);
+        assert_eq!(line.property(properties::BASELINE).unwrap(), ["0", "-40"]);
+        assert_eq!(
+            line.children[0].property(properties::X_BBOXES).unwrap(),
+            ["200", "50", "300", "100", "100", "50", "200", "100"]
+        );
+
+        restore_page(&mut page, &t);
+        let original = rotated_page();
+        assert_eq!(page.children[0].bbox(), original.children[0].bbox());
+        assert_eq!(
+            page.children[0].property(properties::TEXTANGLE).unwrap(),
+            ["180"]
+        );
+    }
+
+    #[test]
+    fn restore_small_skew_is_exact() {
+        let xml = document(
+            "ocr_page ocr_carea ocr_line ocrx_word",
+            r#"<div class="ocr_page" id="page_1" title="bbox 0 0 600 400; textangle 5">
+                <div class="ocr_carea" id="block_1" title="bbox 97 93 341 157">
+                    <span class="ocr_line" id="line_1" title="bbox 100 100 300 120; baseline 0.01 -3">
+                        <span class="ocrx_word" id="word_1" title="bbox 100 100 171 120">word</span>
+                    </span>
+                </div>
+            </div>"#,
+        );
+        let original = HOCR::from_str(&xml).unwrap().elements.remove(0);
+        let mut page = original.clone();
+
+        let t = deskew_page(&mut page, None).unwrap();
+        let line = &page.children[0].children[0];
+        assert!(line.bbox().unwrap().height() > 20);
+        assert_eq!(
+            line.poly().unwrap().transform(&t.inverse().unwrap()).bbox(),
+            Some(BBox::new(100, 100, 300, 120))
+        );
+
+        restore_page(&mut page, &t);
+        assert_eq!(page, original);
+    }
+
+    #[test]
+    fn cuts_become_character_boxes() {
+        let xml = document(
+            "ocr_page ocrx_word",
+            r#"<div class="ocr_page" id="page_1" title="bbox 0 0 600 400; textangle 180">
+                <span class="ocrx_word" id="word_1" title="bbox 300 300 500 350; cuts 100">ab</span>
+            </div>"#,
+        );
+        let mut page = HOCR::from_str(&xml).unwrap().elements.remove(0);
+        deskew_page(&mut page, None).unwrap();
+
+        let word = &page.children[0];
+        assert_eq!(word.property(properties::CUTS), None);
+        let glyphs: Vec<_> = word.glyphs().into_iter().map(|g| g.bbox).collect();
+        assert_eq!(
+            glyphs,
+            [BBox::new(200, 50, 300, 100), BBox::new(100, 50, 200, 100)]
+        );
+    }
+
+    #[test]
+    fn quarter_turn_swaps_page_dimensions() {
+        let mut page = rotated_page();
+        let t = deskew_page(&mut page, Some(90.0)).unwrap();
+        assert_eq!(page.bbox(), Some(BBox::new(0, 0, 400, 600)));
+        assert_eq!(
+            t.apply_bbox(&BBox::new(0, 0, 600, 400)),
+            BBox::new(0, 0, 400, 600)
+        );
+        assert_eq!(page.children[0].baseline(), None);
+
+        assert_eq!(deskew_page(&mut rotated_page(), Some(0.0)), None);
+    }
+}
@@ -0,0 +1,220 @@
+//! Reconstruction of text lines for documents whose words are not grouped into lines.
+//!
+//! Some engines emit `ocrx_word` elements directly under a page or block. [`reconstruct_lines`]
+//! groups such words into new `ocr_line` elements by their vertical overlap, orders the words
+//! in reading direction and gives each line a `bbox` and a fitted `baseline`.
+
+use std::collections::HashSet;
+
+use super::reading_order::{to_left_to_right, visit_document, Direction, BLOCK_ELEMENTS};
+use crate::{
+    baseline::Baseline,
+    edit::collect_ids,
+    element::Element,
+    geometry::BBox,
+    hocr::HOCR,
+    merge::unique_id,
+    property::Property,
+    spec_definitions::elements::{OCRX_WORD, OCR_LINE, OCR_PAGE},
+};
+
+/// Minimum vertical overlap of a word with the previous word of a line, relative to the
+/// smaller of their heights, for the word to continue the line.
+const MIN_OVERLAP: f64 = 0.5;
+
+fn is_word(e: &Element) -> bool {
+    e.element_type == OCRX_WORD && e.bbox().is_some()
+}
+
+fn has_words(e: &Element) -> bool {
+    let container = e.element_type == OCR_PAGE || BLOCK_ELEMENTS.contains(&e.element_type.as_str());
+    container && e.children.iter().any(is_word)
+}
+
+fn vertical_overlap(a: &BBox, b: &BBox) -> f64 {
+    let overlap = a.y1.min(b.y1) - a.y0.max(b.y0);
+    overlap as f64 / a.height().min(b.height()).max(1) as f64
+}
+
+/// Group word boxes into lines.
+///
+/// Returns the indices of the boxes of each line in reading direction, with the lines ordered
+/// top to bottom, or right to left for vertical text.
+pub fn cluster_lines(boxes: &[BBox], direction: Direction) -> Vec<Vec<usize>> {
+    let boxes: Vec<BBox> = boxes
+        .iter()
+        .map(|b| to_left_to_right(b.normalized(), direction))
+        .collect();
+    let mut order: Vec<usize> = (0..boxes.len()).collect();
+    order.sort_by_key(|&i| (boxes[i].x0, boxes[i].y0));
+
+    // comparing with the last word of each line follows skewed lines
+    let mut lines: Vec<(BBox, Vec<usize>)> = vec![];
+    for i in order {
+        let best = lines
+            .iter_mut()
+            .map(|line| {
+                let last = boxes[*line.1.last().expect("lines are not empty")];
+                (vertical_overlap(&last, &boxes[i]), line)
+            })
+            .filter(|(overlap, _)| *overlap >= MIN_OVERLAP)
+            .max_by(|a, b| a.0.total_cmp(&b.0));
+        match best {
+            Some((_, (bbox, words))) => {
+                *bbox = bbox.union(&boxes[i]);
+                words.push(i);
+            }
+            None => lines.push((boxes[i], vec![i])),
+        }
+    }
+
+    lines.sort_by_key(|(bbox, _)| (bbox.y0, bbox.x0));
+    lines.into_iter().map(|(_, words)| words).collect()
+}
+
+fn new_line(words: Vec<Element>, direction: Direction, id: String) -> Element {
+    let boxes: Vec<BBox> = words.iter().filter_map(Element::bbox).collect();
+    let bbox = boxes
+        .iter()
+        .copied()
+        .reduce(|a, b| a.union(&b))
+        .unwrap_or_default();
+
+    let mut properties = vec![Property::BBox(bbox).to_raw()];
+    if direction != Direction::Vertical {
+        if let Some(baseline) = Baseline::fit(&bbox, &boxes) {
+            properties.push(Property::Baseline(baseline.rounded()).to_raw());
+        }
+    }
+
+    Element {
+        element_type: OCR_LINE.to_string(),
+        id: Some(id),
+        tag_name: "span".to_string(),
+        properties,
+        lang: None,
+        attributes: vec![],
+        text: None,
+        tail: None,
+        children: words,
+    }
+}
+
+/// Group the words that are direct children of pages or blocks into new `ocr_line` elements
+/// and return the number of lines created.
+///
+/// The lines take the place of the first of their parent's words; words without a `bbox` and
+/// other children stay where they are. Lines get ids of the form `line_N`, and `ocr_line` is
+/// added to the `ocr-capabilities`. See [`reading_order::reorder`](super::reading_order::reorder)
+/// for how the direction is determined if `direction` is `None`.
+pub fn reconstruct_lines(hocr: &mut HOCR, direction: Option<Direction>) -> usize {
+    let mut ids = HashSet::new();
+    collect_ids(&hocr.elements, &mut ids);
+    let mut created = 0;
+
+    visit_document(hocr, direction, has_words, |e, direction| {
+        let positions: Vec<usize> = (0..e.children.len())
+            .filter(|&i| is_word(&e.children[i]))
+            .collect();
+        let boxes: Vec<BBox> = positions
+            .iter()
+            .filter_map(|&i| e.children[i].bbox())
+            .collect();
+        let groups = cluster_lines(&boxes, direction);
+
+        let mut children: Vec<Option<Element>> = std::mem::take(&mut e.children)
+            .into_iter()
+            .map(Some)
+            .collect();
+        let mut lines = vec![];
+        for group in groups {
+            let words = group
+                .into_iter()
+                .filter_map(|i| children[positions[i]].take())
+                .collect();
+            created += 1;
+            let id = unique_id(format!("line_{created}"), &mut ids);
+            lines.push(new_line(words, direction, id));
+        }
+
+        for (i, child) in children.into_iter().enumerate() {
+            if i == positions[0] {
+                e.children.append(&mut lines);
+            }
+            e.children.extend(child);
+        }
+    });
+
+    if created > 0 && !hocr.capabilities.iter().any(|c| c == OCR_LINE) {
+        hocr.capabilities.push(OCR_LINE.to_string());
+    }
+    created
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn document(lang: &str) -> HOCR {
+        HOCR::from_str(&testing::document(
+            "ocr_page ocrx_word",
+            &format!(
+                r#"<div class="ocr_page" id="page_1" lang="{lang}" title="bbox 0 0 1000 1000">
+                    <span class="ocrx_word" id="w4" title="bbox 10 60 50 80">four</span>
+                    <span class="ocrx_word" id="w2" title="bbox 70 12 110 32">two</span>
+                    <span class="ocrx_word" id="w1" title="bbox 10 10 50 30">one</span>
+                    <span class="ocrx_word" id="w3" title="bbox 130 14 170 34">three</span>
+                    <span class="ocrx_word" id="w5" title="bbox 70 61 110 81">five</span>
+                </div>"#
+            ),
+        ))
+        .unwrap()
+    }
+
+    fn line_words(hocr: &HOCR) -> Vec<Vec<&str>> {
+        hocr.elements[0]
+            .children
+            .iter()
+            .map(|line| {
+                line.children
+                    .iter()
+                    .map(|w| w.id.as_deref().unwrap())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn group_words_into_lines() {
+        let mut hocr = document("eng");
+        assert_eq!(reconstruct_lines(&mut hocr, None), 2);
+        assert_eq!(
+            line_words(&hocr),
+            [vec!["w1", "w2", "w3"], vec!["w4", "w5"]]
+        );
+
+        let line = &hocr.elements[0].children[0];
+        assert_eq!(line.element_type, OCR_LINE);
+        assert_eq!(line.id.as_deref(), Some("line_1"));
+        assert_eq!(line.bbox(), Some(BBox::new(10, 10, 170, 34)));
+        assert_eq!(
+            line.typed_properties()[1],
+            Property::Baseline(Baseline::new(0.033, -5.0))
+        );
+        assert!(hocr.capabilities.contains(&OCR_LINE.to_string()));
+        assert_eq!(hocr.plain_text(), "one two three\nfour five");
+
+        assert_eq!(reconstruct_lines(&mut hocr, None), 0);
+    }
+
+    #[test]
+    fn right_to_left_lines() {
+        let mut hocr = document("heb");
+        reconstruct_lines(&mut hocr, None);
+        assert_eq!(
+            line_words(&hocr),
+            [vec!["w3", "w2", "w1"], vec!["w5", "w4"]]
+        );
+    }
+}
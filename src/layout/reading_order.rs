@@ -0,0 +1,338 @@
+//! Reading-order inference for pages whose blocks are not stored in reading order.
+//!
+//! Blocks (`ocr_carea`, `ocr_par` and `ocrx_block`) are ordered by their boxes, detecting
+//! columns as in [`block_order`]: blocks in the same column are read top to bottom, and
+//! columns in the direction of the script, unless a block spanning several columns, such as a
+//! heading, separates them. Vertical text is read in columns from right to left.
+//!
+//! The inferred order can be applied by [`reorder`], which sorts the children of each
+//! container, or recorded in `order` properties by [`set_order_properties`].
+//!
+//! ```
+//! use hocr_parser::{layout::reading_order, HOCR};
+//!
+//! let xml = std::fs::read_to_string("examples/data/paper-image.hocr").unwrap();
+//! let mut hocr = HOCR::from_str(&xml).unwrap();
+//! reading_order::reorder(&mut hocr, None);
+//! ```
+
+use crate::{
+    element::Element,
+    geometry::BBox,
+    hocr::HOCR,
+    property::Property,
+    spec_definitions::{
+        elements::{OCRX_BLOCK, OCR_CAREA, OCR_PAR},
+        properties,
+    },
+};
+
+/// Element types ordered by this module.
+pub(crate) const BLOCK_ELEMENTS: [&str; 3] = [OCR_CAREA, OCR_PAR, OCRX_BLOCK];
+
+/// Primary language subtags and Tesseract language codes of right-to-left scripts.
+const RTL_LANGUAGES: [&str; 18] = [
+    "ar", "ara", "he", "heb", "fa", "fas", "per", "ur", "urd", "yi", "yid", "ps", "pus", "syr",
+    "dv", "div", "ug", "uig",
+];
+
+/// Direction in which columns of text are read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Direction {
+    /// Columns from left to right, e.g. Latin script.
+    #[default]
+    LeftToRight,
+    /// Columns from right to left, e.g. Arabic or Hebrew.
+    RightToLeft,
+    /// Vertical text set in columns read from right to left, e.g. vertical Japanese.
+    Vertical,
+}
+
+impl Direction {
+    /// The direction of a language tag. Tesseract's `_vert` models, such as `jpn_vert`, are
+    /// vertical.
+    pub fn from_lang(lang: &str) -> Self {
+        let lang = lang.to_ascii_lowercase();
+        let primary = lang.split(['-', '_']).next().unwrap_or("");
+        if lang.ends_with("_vert") {
+            Self::Vertical
+        } else if RTL_LANGUAGES.contains(&primary) {
+            Self::RightToLeft
+        } else {
+            Self::LeftToRight
+        }
+    }
+
+    /// The direction of an element from its `dir` attribute or `lang`, if it has either.
+    pub fn of_element(e: &Element) -> Option<Self> {
+        let dir = e.attributes.iter().find(|(name, _)| name == "dir");
+        match dir.map(|(_, value)| value.to_ascii_lowercase()).as_deref() {
+            Some("rtl") => Some(Self::RightToLeft),
+            Some("ltr") => Some(Self::LeftToRight),
+            _ => e.lang.as_deref().map(Self::from_lang),
+        }
+    }
+}
+
+/// Map a box so that reading proceeds left to right and top to bottom.
+pub(crate) fn to_left_to_right(b: BBox, direction: Direction) -> BBox {
+    match direction {
+        Direction::LeftToRight => b,
+        Direction::RightToLeft => BBox::new(-b.x1, b.y0, -b.x0, b.y1),
+        Direction::Vertical => BBox::new(b.y0, -b.x1, b.y1, -b.x0),
+    }
+}
+
+fn overlaps_horizontally(a: &BBox, b: &BBox) -> bool {
+    a.x0 < b.x1 && b.x0 < a.x1
+}
+
+/// Whether `c` lies vertically within the range of `a` and `b` and horizontally spans the gap
+/// from `a` to `b`, e.g. a heading between two sections set in columns.
+fn separates(c: &BBox, a: &BBox, b: &BBox) -> bool {
+    c.y1 >= a.y0.min(b.y0) && c.y0 <= a.y1.max(b.y1) && c.x0 < a.x1 && c.x1 > b.x0
+}
+
+/// Returns the indices of `boxes` in reading order.
+///
+/// A box comes before another if they overlap horizontally and it starts higher up, or if it
+/// lies entirely before the other in reading direction and no third box separates them. The
+/// boxes are sorted topologically by these constraints, preferring boxes higher up and then
+/// earlier in reading direction.
+pub fn block_order(boxes: &[BBox], direction: Direction) -> Vec<usize> {
+    let boxes: Vec<BBox> = boxes
+        .iter()
+        .map(|b| to_left_to_right(b.normalized(), direction))
+        .collect();
+    // a box separating `a` from a box after it crosses the right edge of `a`, which in
+    // column layouts only few boxes do
+    let crossing: Vec<Vec<&BBox>> = boxes
+        .iter()
+        .map(|a| {
+            boxes
+                .iter()
+                .filter(|c| c.x0 < a.x1 && c.x1 > a.x1)
+                .collect()
+        })
+        .collect();
+    let before: Vec<Vec<bool>> = boxes
+        .iter()
+        .zip(&crossing)
+        .map(|(a, crossing)| {
+            boxes
+                .iter()
+                .map(|b| {
+                    if overlaps_horizontally(a, b) {
+                        a.y0 < b.y0
+                    } else {
+                        a.x1 <= b.x0 && !crossing.iter().any(|c| separates(c, a, b))
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    let n = boxes.len();
+    let mut predecessors: Vec<usize> = (0..n)
+        .map(|j| (0..n).filter(|&i| before[i][j]).count())
+        .collect();
+    let mut done = vec![false; n];
+    let mut order = Vec::with_capacity(n);
+    while order.len() < n {
+        let remaining = (0..n).filter(|&i| !done[i]);
+        let position = |&i: &usize| (boxes[i].y0, boxes[i].x0);
+        // fall back to position if overlapping boxes form a cycle
+        let next = remaining
+            .clone()
+            .filter(|&i| predecessors[i] == 0)
+            .min_by_key(position)
+            .or_else(|| remaining.min_by_key(position))
+            .expect("boxes remain");
+
+        done[next] = true;
+        order.push(next);
+        for j in (0..n).filter(|&j| !done[j]) {
+            if before[next][j] {
+                predecessors[j] = predecessors[j].saturating_sub(1);
+            }
+        }
+    }
+    order
+}
+
+/// Returns the indices of the children of `e` in reading order.
+///
+/// Children without a `bbox` keep their relative order after all children with one.
+pub fn child_order(e: &Element, direction: Direction) -> Vec<usize> {
+    let (boxes, indices): (Vec<_>, Vec<_>) = e
+        .children
+        .iter()
+        .enumerate()
+        .filter_map(|(i, child)| Some((child.bbox()?.normalized(), i)))
+        .unzip();
+
+    let mut order: Vec<usize> = block_order(&boxes, direction)
+        .into_iter()
+        .map(|i| indices[i])
+        .collect();
+    order.extend((0..e.children.len()).filter(|i| !indices.contains(i)));
+    order
+}
+
+fn has_blocks(e: &Element) -> bool {
+    e.children
+        .iter()
+        .any(|child| BLOCK_ELEMENTS.contains(&child.element_type.as_str()))
+}
+
+/// Apply `f` to every element matching `filter`, with its reading direction.
+fn visit(
+    e: &mut Element,
+    inherited: Direction,
+    fixed: Option<Direction>,
+    filter: fn(&Element) -> bool,
+    f: &mut impl FnMut(&mut Element, Direction),
+) {
+    let direction = fixed
+        .or_else(|| Direction::of_element(e))
+        .unwrap_or(inherited);
+    if filter(e) {
+        f(e, direction);
+    }
+    for child in &mut e.children {
+        visit(child, direction, fixed, filter, f);
+    }
+}
+
+/// Apply `f` to every element of the document matching `filter`, with its reading direction:
+/// `direction` if given, and otherwise the direction of its `dir` attribute or `lang`, inherited
+/// from its ancestors and the `ocr-langs` metadata.
+pub(crate) fn visit_document(
+    hocr: &mut HOCR,
+    direction: Option<Direction>,
+    filter: fn(&Element) -> bool,
+    mut f: impl FnMut(&mut Element, Direction),
+) {
+    let inherited = match hocr.langs.as_deref() {
+        Some([lang]) => Direction::from_lang(lang),
+        _ => Direction::default(),
+    };
+    for e in &mut hocr.elements {
+        visit(e, inherited, direction, filter, &mut f);
+    }
+}
+
+/// Sort the children of every element containing blocks into reading order.
+///
+/// The direction is taken from `direction` if given, and otherwise from the `dir` attributes
+/// and `lang` of the elements and the `ocr-langs` metadata, defaulting to left to right.
+pub fn reorder(hocr: &mut HOCR, direction: Option<Direction>) {
+    visit_document(hocr, direction, has_blocks, |e, direction| {
+        let order = child_order(e, direction);
+        let mut children: Vec<Option<Element>> = std::mem::take(&mut e.children)
+            .into_iter()
+            .map(Some)
+            .collect();
+        e.children = order
+            .into_iter()
+            .filter_map(|i| children[i].take())
+            .collect();
+    });
+}
+
+/// Set the `order` property of the children of every element containing blocks to their
+/// 1-based position in reading order, without moving them.
+///
+/// See [`reorder`] for how the direction is determined.
+pub fn set_order_properties(hocr: &mut HOCR, direction: Option<Direction>) {
+    visit_document(hocr, direction, has_blocks, |e, direction| {
+        for (position, i) in child_order(e, direction).into_iter().enumerate() {
+            let order = Property::Order(position as u32 + 1);
+            e.children[i].set_property(properties::ORDER, order.values());
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn document(lang: &str) -> HOCR {
+        // a title across two columns, with blocks in detection order
+        HOCR::from_str(&testing::document(
+            "ocr_page ocr_carea",
+            &format!(
+                r#"<div class="ocr_page" id="page_1" lang="{lang}" title="bbox 0 0 1000 1000">
+                    <div class="ocr_carea" id="left_bottom" title="bbox 100 500 450 900"></div>
+                    <div class="ocr_carea" id="right_top" title="bbox 550 200 900 450"></div>
+                    <div class="ocr_carea" id="title" title="bbox 100 50 900 150"></div>
+                    <div class="ocr_carea" id="left_top" title="bbox 100 200 450 460"></div>
+                    <div class="ocr_carea" id="right_bottom" title="bbox 550 500 900 900"></div>
+                </div>"#
+            ),
+        ))
+        .unwrap()
+    }
+
+    fn ids(hocr: &HOCR) -> Vec<&str> {
+        hocr.elements[0]
+            .children
+            .iter()
+            .map(|e| e.id.as_deref().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn two_columns_with_title() {
+        let mut hocr = document("eng");
+        reorder(&mut hocr, None);
+        assert_eq!(
+            ids(&hocr),
+            [
+                "title",
+                "left_top",
+                "left_bottom",
+                "right_top",
+                "right_bottom"
+            ]
+        );
+
+        let mut hocr = document("ara");
+        reorder(&mut hocr, None);
+        assert_eq!(
+            ids(&hocr),
+            [
+                "title",
+                "right_top",
+                "right_bottom",
+                "left_top",
+                "left_bottom"
+            ]
+        );
+    }
+
+    #[test]
+    fn order_properties() {
+        let mut hocr = document("eng");
+        set_order_properties(&mut hocr, Some(Direction::LeftToRight));
+
+        let orders: Vec<_> = hocr.elements[0]
+            .children
+            .iter()
+            .map(|e| e.property(properties::ORDER).unwrap()[0].as_str())
+            .collect();
+        assert_eq!(orders, ["3", "4", "1", "2", "5"]);
+        assert_eq!(ids(&hocr)[0], "left_bottom");
+    }
+
+    #[test]
+    fn directions() {
+        assert_eq!(Direction::from_lang("he-IL"), Direction::RightToLeft);
+        assert_eq!(Direction::from_lang("jpn_vert"), Direction::Vertical);
+        assert_eq!(Direction::from_lang("deu"), Direction::LeftToRight);
+
+        let boxes = [BBox::new(0, 0, 10, 100), BBox::new(20, 0, 30, 100)];
+        assert_eq!(block_order(&boxes, Direction::Vertical), [1, 0]);
+    }
+}
@@ -0,0 +1,666 @@
+//! Inference of the logical structure of documents that only carry typesetting elements.
+//!
+//! Tesseract describes pages as blocks, paragraphs and lines. [`infer_structure`] classifies
+//! `ocr_par` elements by their font size relative to the body text, their position and the
+//! space above them, with thresholds given by [`StructureOptions`]:
+//!
+//! - `ocr_title`: a large paragraph near the top of the first page
+//! - `ocr_chapter` and `ocr_section`: short paragraphs set larger than the body text
+//! - `ocr_subsection`: short bold paragraphs of body size with space above them
+//! - `ocr_caption`: short paragraphs starting with a label such as `Figure 1:` or `Table 2.`,
+//!   or the only short paragraph of a block next to an image
+//!
+//! Font sizes are taken from `x_fsize` (see [`FontInfo`]) and estimated from line heights if
+//! the document has none.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    edit::collect_ids,
+    element::Element,
+    font::FontInfo,
+    geometry::BBox,
+    hocr::HOCR,
+    merge::unique_id,
+    property::Property,
+    recompute::children_bbox,
+    spec_definitions::elements::{
+        OCRX_WORD, OCR_CAPTION, OCR_CHAPTER, OCR_FLOAT, OCR_IMAGE, OCR_LINEDRAWING, OCR_PAGE,
+        OCR_PAR, OCR_PHOTO, OCR_SECTION, OCR_SUBSECTION, OCR_TITLE,
+    },
+    summary::LINE_ELEMENTS,
+};
+
+/// Element types treated as images when looking for captions.
+const IMAGE_ELEMENTS: [&str; 4] = [OCR_IMAGE, OCR_PHOTO, OCR_LINEDRAWING, OCR_FLOAT];
+
+/// Heading classes by level, from chapters to subsections.
+const SECTION_ELEMENTS: [&str; 3] = [OCR_CHAPTER, OCR_SECTION, OCR_SUBSECTION];
+
+/// Options for [`infer_structure_with_options`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructureOptions {
+    /// Minimum size of a title relative to the body text. Default: 1.8.
+    pub title_ratio: f64,
+    /// Fraction of the first page, from the top, in which the title must start. Default: 0.33.
+    pub title_region: f64,
+    /// Minimum size of a chapter heading relative to the body text. Default: 1.5.
+    pub chapter_ratio: f64,
+    /// Minimum size of a section heading relative to the body text. Default: 1.2.
+    pub section_ratio: f64,
+    /// Minimum space above a bold subsection heading, in body line heights. Default: 1.0.
+    pub subsection_gap: f64,
+    /// Maximum number of lines of a title, heading or caption. Default: 3.
+    pub max_heading_lines: usize,
+    /// Words that start a caption when followed by a number ending in `:` or `.`, e.g.
+    /// `Figure 7:`. Default: `Figure`, `Fig.` and `Table`.
+    pub caption_prefixes: Vec<String>,
+    /// Wrap each heading and the paragraphs following it in its block, up to the next heading
+    /// of the same or a higher level, in a new `ocr_chapter`, `ocr_section` or
+    /// `ocr_subsection` element instead of reclassifying the heading. Default: false.
+    pub wrap_sections: bool,
+}
+
+impl Default for StructureOptions {
+    fn default() -> Self {
+        Self {
+            title_ratio: 1.8,
+            title_region: 0.33,
+            chapter_ratio: 1.5,
+            section_ratio: 1.2,
+            subsection_gap: 1.0,
+            max_heading_lines: 3,
+            caption_prefixes: ["Figure", "Fig.", "Table"].map(String::from).to_vec(),
+            wrap_sections: false,
+        }
+    }
+}
+
+/// A paragraph classified by [`infer_structure`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StructureChange {
+    /// Id of the paragraph.
+    pub id: Option<String>,
+    /// The logical class assigned, e.g. `ocr_section`.
+    pub element_type: String,
+    /// Whether the paragraph was wrapped in a new element of that class, rather than
+    /// reclassified.
+    pub wrapped: bool,
+}
+
+/// Measurements of an `ocr_par` element.
+struct Paragraph {
+    path: Vec<usize>,
+    page: usize,
+    page_bbox: Option<BBox>,
+    bbox: Option<BBox>,
+    lines: usize,
+    characters: usize,
+    /// Mean font size of the words in points, weighted by characters.
+    size: Option<f64>,
+    /// Median height of the lines in pixels.
+    line_height: Option<f64>,
+    /// Whether most characters are bold.
+    bold: bool,
+    /// Space to the previous paragraph on the page in pixels.
+    gap_above: Option<i32>,
+    /// Whether the paragraph is the only one of its block and the block is next to an image.
+    beside_image: bool,
+    text: String,
+}
+
+#[derive(Default)]
+struct Collector {
+    paragraphs: Vec<Paragraph>,
+    pages: usize,
+    page_bbox: Option<BBox>,
+    previous_bottom: Option<i32>,
+}
+
+fn median(mut values: Vec<f64>) -> Option<f64> {
+    values.sort_by(f64::total_cmp);
+    values.get(values.len() / 2).copied()
+}
+
+/// The median of `(value, weight)` pairs.
+fn weighted_median(mut values: Vec<(f64, usize)>) -> Option<f64> {
+    values.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let half = values.iter().map(|v| v.1).sum::<usize>() / 2;
+    let mut seen = 0;
+    for (value, weight) in &values {
+        seen += weight;
+        if seen > half {
+            return Some(*value);
+        }
+    }
+    values.last().map(|v| v.0)
+}
+
+/// Count the characters of the words of `e`, in total, by size and in bold.
+fn measure_words(
+    e: &Element,
+    font: &FontInfo,
+    sizes: &mut (f64, usize),
+    total: &mut (usize, usize),
+) {
+    for child in &e.children {
+        let font = FontInfo::of_element(child).inherit(font);
+        if child.element_type == OCRX_WORD {
+            let characters = child.plain_text().chars().count();
+            total.0 += characters;
+            if font.bold {
+                total.1 += characters;
+            }
+            if let Some(size) = font.size {
+                sizes.0 += size * characters as f64;
+                sizes.1 += characters;
+            }
+        } else {
+            measure_words(child, &font, sizes, total);
+        }
+    }
+}
+
+fn measure(e: &Element, font: &FontInfo, path: Vec<usize>, beside_image: bool, c: &mut Collector) {
+    let lines: Vec<&Element> = e
+        .children
+        .iter()
+        .filter(|l| LINE_ELEMENTS.contains(&l.element_type.as_str()))
+        .collect();
+    let line_height = median(
+        lines
+            .iter()
+            .filter_map(|l| l.bbox())
+            .map(|b| b.height() as f64)
+            .collect(),
+    );
+
+    let mut sizes = (0.0, 0);
+    let mut total = (0, 0);
+    measure_words(e, font, &mut sizes, &mut total);
+
+    let bbox = e.bbox();
+    let gap_above = bbox.zip(c.previous_bottom).map(|(b, bottom)| b.y0 - bottom);
+    if let Some(b) = bbox {
+        c.previous_bottom = Some(b.y1);
+    }
+
+    c.paragraphs.push(Paragraph {
+        path,
+        page: c.pages.saturating_sub(1),
+        page_bbox: c.page_bbox,
+        bbox,
+        lines: lines.len(),
+        characters: total.0,
+        size: (sizes.1 > 0).then(|| sizes.0 / sizes.1 as f64),
+        line_height,
+        bold: total.0 > 0 && total.1 * 2 > total.0,
+        gap_above,
+        beside_image,
+        text: e.plain_text(),
+    });
+}
+
+fn collect(
+    elements: &[Element],
+    font: &FontInfo,
+    path: &mut Vec<usize>,
+    beside_image: bool,
+    c: &mut Collector,
+) {
+    let is_image = |i: usize| {
+        elements
+            .get(i)
+            .is_some_and(|e| IMAGE_ELEMENTS.contains(&e.element_type.as_str()))
+    };
+
+    for (i, e) in elements.iter().enumerate() {
+        path.push(i);
+        let font = FontInfo::of_element(e).inherit(font);
+        let next_to_image = (i > 0 && is_image(i - 1)) || is_image(i + 1);
+
+        if e.element_type == OCR_PAGE {
+            c.pages += 1;
+            c.page_bbox = e.bbox();
+            c.previous_bottom = None;
+        }
+
+        if e.element_type == OCR_PAR {
+            let only = elements.len() == 1;
+            measure(
+                e,
+                &font,
+                path.clone(),
+                next_to_image || (only && beside_image),
+                c,
+            );
+        } else {
+            collect(&e.children, &font, path, next_to_image, c);
+        }
+        path.pop();
+    }
+}
+
+/// Logical class of a paragraph: a title, a caption or a heading level.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Class {
+    Title,
+    Caption,
+    Heading(usize),
+}
+
+impl Class {
+    fn element_type(&self) -> &'static str {
+        match self {
+            Class::Title => OCR_TITLE,
+            Class::Caption => OCR_CAPTION,
+            Class::Heading(level) => SECTION_ELEMENTS[*level],
+        }
+    }
+}
+
+/// Whether `text` starts with a caption label: a prefix followed by a number ending in `:` or
+/// `.`, such as `Figure 1:`, `Fig. 2a.` or `Table 3.1:`.
+fn has_caption_label(text: &str, prefixes: &[String]) -> bool {
+    let text = text.trim_start();
+    prefixes.iter().any(|prefix| {
+        let Some(number) = text
+            .strip_prefix(prefix.as_str())
+            .and_then(|rest| rest.split_whitespace().next())
+        else {
+            return false;
+        };
+        let Some(number) = number.strip_suffix([':', '.']) else {
+            return false;
+        };
+        number.starts_with(|c: char| c.is_ascii_digit())
+            && number
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '.')
+    })
+}
+
+fn is_caption(p: &Paragraph, options: &StructureOptions) -> bool {
+    (p.beside_image || has_caption_label(&p.text, &options.caption_prefixes))
+        && p.lines <= options.max_heading_lines
+}
+
+fn classify(paragraphs: &[Paragraph], options: &StructureOptions) -> Vec<Option<Class>> {
+    let weighted = |value: fn(&Paragraph) -> Option<f64>| {
+        weighted_median(
+            paragraphs
+                .iter()
+                .filter_map(|p| Some((value(p)?, p.characters)))
+                .collect(),
+        )
+    };
+    let body_size = weighted(|p| p.size).filter(|s| *s > 0.0);
+    let body_line_height = weighted(|p| p.line_height).filter(|h| *h > 0.0);
+    let total: usize = paragraphs.iter().map(|p| p.characters).sum();
+    let bold: usize = paragraphs
+        .iter()
+        .filter(|p| p.bold)
+        .map(|p| p.characters)
+        .sum();
+    let body_bold = bold * 2 > total;
+
+    let ratio = |p: &Paragraph| match (p.size, body_size) {
+        (Some(size), Some(body)) => Some(size / body),
+        _ => Some(p.line_height? / body_line_height?),
+    };
+
+    let mut has_title = false;
+    paragraphs
+        .iter()
+        .map(|p| {
+            if p.characters == 0 {
+                return None;
+            }
+            if is_caption(p, options) {
+                return Some(Class::Caption);
+            }
+            if p.lines > options.max_heading_lines {
+                return None;
+            }
+
+            let ratio = ratio(p).unwrap_or(1.0);
+            let near_top = match (p.bbox, p.page_bbox) {
+                (Some(b), Some(page)) => {
+                    ((b.y0 - page.y0) as f64) < options.title_region * page.height() as f64
+                }
+                _ => false,
+            };
+            let spaced = match (p.gap_above, body_line_height) {
+                (Some(gap), Some(height)) => gap as f64 >= options.subsection_gap * height,
+                _ => p.gap_above.is_none(),
+            };
+
+            if !has_title && p.page == 0 && near_top && ratio >= options.title_ratio {
+                has_title = true;
+                Some(Class::Title)
+            } else if ratio >= options.chapter_ratio {
+                Some(Class::Heading(0))
+            } else if ratio >= options.section_ratio {
+                Some(Class::Heading(1))
+            } else if p.bold && !body_bold && spaced {
+                Some(Class::Heading(2))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Wrap headings among `e.children` and the siblings following them in section elements.
+fn wrap(
+    e: &mut Element,
+    path: &mut Vec<usize>,
+    classes: &HashMap<Vec<usize>, Class>,
+    ids: &mut HashSet<String>,
+    created: &mut [usize; 3],
+) {
+    for (i, child) in e.children.iter_mut().enumerate() {
+        path.push(i);
+        wrap(child, path, classes, ids, created);
+        path.pop();
+    }
+
+    let level_of = |i: usize, path: &mut Vec<usize>| {
+        path.push(i);
+        let class = classes.get(path.as_slice()).copied();
+        path.pop();
+        match class {
+            Some(Class::Heading(level)) => Some(level),
+            _ => None,
+        }
+    };
+    let levels: Vec<Option<usize>> = (0..e.children.len()).map(|i| level_of(i, path)).collect();
+    if levels.iter().all(Option::is_none) {
+        return;
+    }
+
+    fn close(open: &mut Vec<(usize, Element)>, output: &mut Vec<Element>, level: usize) {
+        while open.last().is_some_and(|(l, _)| *l >= level) {
+            let (_, mut section) = open.pop().expect("a section is open");
+            if let Some(bbox) = children_bbox(&section) {
+                section.properties = vec![Property::BBox(bbox).to_raw()];
+            }
+            match open.last_mut() {
+                Some((_, parent)) => parent.children.push(section),
+                None => output.push(section),
+            }
+        }
+    }
+
+    let mut output = vec![];
+    let mut open: Vec<(usize, Element)> = vec![];
+    for (child, level) in std::mem::take(&mut e.children).into_iter().zip(levels) {
+        if let Some(level) = level {
+            close(&mut open, &mut output, level);
+            let element_type = SECTION_ELEMENTS[level];
+            let name = element_type.trim_start_matches("ocr_");
+            created[level] += 1;
+            let n = created[level];
+            open.push((
+                level,
+                Element {
+                    element_type: element_type.to_string(),
+                    id: Some(unique_id(format!("{name}_{n}"), ids)),
+                    tag_name: "div".to_string(),
+                    properties: vec![],
+                    lang: None,
+                    attributes: vec![],
+                    text: None,
+                    tail: None,
+                    children: vec![],
+                },
+            ));
+        }
+        match open.last_mut() {
+            Some((_, section)) => section.children.push(child),
+            None => output.push(child),
+        }
+    }
+    close(&mut open, &mut output, 0);
+    e.children = output;
+}
+
+fn element_at<'a>(elements: &'a mut [Element], path: &[usize]) -> &'a mut Element {
+    let (first, rest) = path.split_first().expect("paths are not empty");
+    rest.iter()
+        .fold(&mut elements[*first], |e, &i| &mut e.children[i])
+}
+
+/// Classify paragraphs as titles, headings and captions using the default
+/// [`StructureOptions`]. See [`infer_structure_with_options`].
+pub fn infer_structure(hocr: &mut HOCR) -> Vec<StructureChange> {
+    infer_structure_with_options(hocr, &StructureOptions::default())
+}
+
+/// Classify paragraphs as titles, headings and captions, reclassifying them or, for headings
+/// with [`StructureOptions::wrap_sections`], wrapping them in section elements.
+///
+/// The new classes are added to the `ocr-capabilities`. Returns the classified paragraphs in
+/// document order.
+pub fn infer_structure_with_options(
+    hocr: &mut HOCR,
+    options: &StructureOptions,
+) -> Vec<StructureChange> {
+    let mut collector = Collector::default();
+    collect(
+        &hocr.elements,
+        &FontInfo::default(),
+        &mut vec![],
+        false,
+        &mut collector,
+    );
+    let paragraphs = collector.paragraphs;
+    let classes = classify(&paragraphs, options);
+
+    let mut changes = vec![];
+    let mut headings = HashMap::new();
+    for (p, class) in paragraphs.iter().zip(classes) {
+        let Some(class) = class else { continue };
+        let e = element_at(&mut hocr.elements, &p.path);
+        let wrapped = options.wrap_sections && matches!(class, Class::Heading(_));
+        if wrapped {
+            headings.insert(p.path.clone(), class);
+        } else {
+            e.element_type = class.element_type().to_string();
+        }
+        changes.push(StructureChange {
+            id: e.id.clone(),
+            element_type: class.element_type().to_string(),
+            wrapped,
+        });
+    }
+
+    if !headings.is_empty() {
+        let mut ids = HashSet::new();
+        collect_ids(&hocr.elements, &mut ids);
+        let mut root = Element {
+            element_type: String::new(),
+            id: None,
+            tag_name: String::new(),
+            properties: vec![],
+            lang: None,
+            attributes: vec![],
+            text: None,
+            tail: None,
+            children: std::mem::take(&mut hocr.elements),
+        };
+        wrap(&mut root, &mut vec![], &headings, &mut ids, &mut [0; 3]);
+        hocr.elements = root.children;
+    }
+
+    for change in &changes {
+        if !hocr.capabilities.contains(&change.element_type) {
+            hocr.capabilities.push(change.element_type.clone());
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    /// A paragraph with one line of `height` for each line of `text`.
+    fn par(id: &str, y: i32, height: i32, bold: bool, text: &str) -> String {
+        let lines: Vec<String> = text
+            .lines()
+            .enumerate()
+            .map(|(l, line)| {
+                let words: Vec<String> = line
+                    .split(' ')
+                    .enumerate()
+                    .map(|(i, w)| {
+                        let w = if bold {
+                            format!("<strong>{w}</strong>")
+                        } else {
+                            w.to_string()
+                        };
+                        format!(r#"<span class="ocrx_word" id="{id}_l{l}_w{i}">{w}</span>"#)
+                    })
+                    .collect();
+                let y = y + l as i32 * height;
+                format!(
+                    r#"<span class="ocr_line" id="{id}_l{l}" title="bbox 100 {y} 900 {}">{}</span>"#,
+                    y + height,
+                    words.join(" ")
+                )
+            })
+            .collect();
+        format!(
+            r#"<p class="ocr_par" id="{id}" title="bbox 100 {y} 900 {}">{}</p>"#,
+            y + lines.len() as i32 * height,
+            lines.join("\n")
+        )
+    }
+
+    fn document() -> HOCR {
+        let body = "Lorem ipsum dolor sit amet consectetur adipiscing elit sed do eiusmod";
+        page(&[
+            par("title", 50, 60, false, "A Study of Things"),
+            par("p1", 150, 20, false, body),
+            par("p2", 175, 20, false, body),
+            par("heading", 230, 32, false, "Introduction"),
+            par("p3", 270, 20, false, body),
+            par("bold", 330, 20, true, "Background"),
+            par("p4", 355, 20, false, body),
+            par("caption", 400, 20, false, "Figure 1: A thing"),
+        ])
+    }
+
+    fn page(paragraphs: &[String]) -> HOCR {
+        HOCR::from_str(&testing::document(
+            "ocr_page ocr_carea ocr_par ocr_line ocrx_word",
+            &format!(
+                r#"<div class="ocr_page" id="page_1" title="bbox 0 0 1000 1400">
+                    <div class="ocr_carea" id="block_1" title="bbox 100 50 900 420">{}</div>
+                </div>"#,
+                paragraphs.join("\n")
+            ),
+        ))
+        .unwrap()
+    }
+
+    fn classes(hocr: &HOCR) -> Vec<(&str, &str)> {
+        hocr.iter_document_order()
+            .filter(|e| e.id.as_deref().is_some_and(|id| !id.contains('_')))
+            .map(|e| (e.id.as_deref().unwrap(), e.element_type.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn reclassify_paragraphs() {
+        let mut hocr = document();
+        let changes = infer_structure(&mut hocr);
+        assert_eq!(changes.len(), 4);
+        assert_eq!(
+            classes(&hocr),
+            [
+                ("title", OCR_TITLE),
+                ("p1", OCR_PAR),
+                ("p2", OCR_PAR),
+                ("heading", OCR_CHAPTER),
+                ("p3", OCR_PAR),
+                ("bold", OCR_SUBSECTION),
+                ("p4", OCR_PAR),
+                ("caption", OCR_CAPTION),
+            ]
+        );
+        assert!(hocr.capabilities.contains(&OCR_TITLE.to_string()));
+
+        let options = StructureOptions {
+            chapter_ratio: 2.0,
+            ..Default::default()
+        };
+        let mut hocr = document();
+        infer_structure_with_options(&mut hocr, &options);
+        assert_eq!(classes(&hocr)[3], ("heading", OCR_SECTION));
+    }
+
+    #[test]
+    fn wrap_sections() {
+        let mut hocr = document();
+        let options = StructureOptions {
+            wrap_sections: true,
+            ..Default::default()
+        };
+        let changes = infer_structure_with_options(&mut hocr, &options);
+        assert!(changes.iter().any(|c| c.wrapped));
+
+        let block = &hocr.elements[0].children[0];
+        let ids: Vec<_> = block
+            .children
+            .iter()
+            .map(|e| e.id.as_deref().unwrap())
+            .collect();
+        assert_eq!(ids, ["title", "p1", "p2", "chapter_1"]);
+
+        let chapter = &block.children[3];
+        assert_eq!(chapter.element_type, OCR_CHAPTER);
+        assert_eq!(chapter.bbox(), Some(BBox::new(100, 230, 900, 420)));
+        let ids: Vec<_> = chapter
+            .children
+            .iter()
+            .map(|e| e.id.as_deref().unwrap())
+            .collect();
+        assert_eq!(ids, ["heading", "p3", "subsection_1"]);
+        assert_eq!(chapter.children[2].children.len(), 3);
+        assert_eq!(hocr.plain_text().lines().next(), Some("A Study of Things"));
+    }
+
+    #[test]
+    fn captions_need_a_label_and_few_lines() {
+        let body = "Lorem ipsum dolor sit amet consectetur adipiscing elit sed do eiusmod";
+        let more = format!("{body}\n{body}\n{body}");
+        let mut hocr = page(&[
+            par("p1", 50, 20, false, body),
+            par("short", 80, 20, false, "Table 2 lists the results"),
+            par(
+                "prose",
+                110,
+                20,
+                false,
+                &format!("Table 2 lists the\n{more}"),
+            ),
+            par("long", 200, 20, false, &format!("Table 3: Results\n{more}")),
+            par("caption", 290, 20, false, "Table 4. Results"),
+        ]);
+        infer_structure(&mut hocr);
+        assert_eq!(
+            classes(&hocr),
+            [
+                ("p1", OCR_PAR),
+                ("short", OCR_PAR),
+                ("prose", OCR_PAR),
+                ("long", OCR_PAR),
+                ("caption", OCR_CAPTION),
+            ]
+        );
+    }
+}
@@ -4,21 +4,78 @@
 //! 
 //! ## Design 
 //! 
-//! This parser uses [`roxmltree`] to parse the XHTML. It simplifies provides easy access to the hOCR data embedded through the [`HOCR`] and [`Element`] structs, as well as their "borrowed" counterparts ([`HOCRBorrowed`], [`ElementBorrowed`]) to prevent allocating for property names.
+//! This parser uses [`roxmltree`] to parse the XHTML. It simplifies provides easy access to the hOCR data embedded through the [`HOCR`] and [`Element`] structs, as well as their "borrowed" counterparts ([`HOCRBorrowed`], [`ElementBorrowed`]) to prevent allocating for property names. [`HOCRView`] and [`ElementView`] go further and wrap the XML nodes without allocating, parsing attributes on demand.
 //! 
 //! The parser does not validate if the file adheres to the hOCR specification. It checks required metadata and validity of hOCR element and property names but does not check property values.
 
+/// Reading hOCR files from zip and tar archives.
+#[cfg(any(feature = "zip", feature = "tar"))]
+pub mod archive;
+mod baseline;
+mod compression;
+mod edit;
 mod element;
+mod encoding;
 mod error;
+mod font;
+mod geometry;
+mod glyph;
 mod iter;
 mod hocr;
+#[cfg(feature = "html5")]
+mod html5;
+mod merge;
+mod owned;
+mod page;
 mod parsing;
+mod polygon;
+mod property;
+mod recompute;
+mod summary;
+#[cfg(test)]
+mod testing;
+mod text;
+mod traits;
+mod units;
+mod validate;
+mod view;
+mod writer;
+/// Processing many hOCR files at once.
+#[cfg(feature = "batch")]
+pub mod batch;
+/// A lossless JSON representation of hOCR documents.
+#[cfg(feature = "serde")]
+pub mod json;
+/// Layout analysis on the geometry of hOCR elements.
+pub mod layout;
 /// Contains the element and property names defined in the hOCR specification.
 pub mod spec_definitions;
+/// Word confidence statistics and low-confidence reports.
+pub mod stats;
+/// Streaming parser for large hOCR files.
+#[cfg(feature = "stream")]
+pub mod stream;
 
-pub use error::{HOCRParserError, Result};
-pub use hocr::{HOCR, HOCRBorrowed};
+pub use error::{ErrorLocation, HOCRParserError, Result};
+pub use iter::ElementsIterator;
+pub use hocr::{DocumentMetadata, HOCRBorrowed, HOCR};
+pub use merge::MergeOptions;
+pub use owned::HOCROwned;
 pub use element::{Element, ElementBorrowed};
+pub use font::{FontCount, FontInfo, FontInventory};
+pub use baseline::Baseline;
+pub use geometry::{BBox, Transform};
+pub use glyph::{parse_cuts, Cut, Glyph};
+pub use page::{IntoPages, Page};
+pub use polygon::Polygon;
+pub use property::Property;
+pub use recompute::{BBoxChange, BBoxChangeKind, RecomputeOptions};
+pub use traits::{HocrDocument, HocrElement};
+pub use summary::{FontUsage, PageDimensions, Summary};
+pub use units::{Unit, Units};
+pub use parsing::{Properties, Values};
+pub use validate::ValidationIssue;
+pub use view::{ElementView, HOCRView};
 
 pub use roxmltree;
 
@@ -29,7 +86,7 @@ mod tests {
     #[test]
     fn parse_quoted_properties() {
         let property = "image \"Screenshot 2024-05-12 at 14.21.17.png\"; bbox 0 0 796 1314; ppageno 0; scan_res 144 144";
-        let res = parsing::parse_properties(&property);
+        let res = parsing::parse_properties(property);
         println!("{:?}", res);
         let image_prop = res.iter().find(|(n, _)| *n == "image").unwrap();
         assert_eq!(image_prop.1, vec!["Screenshot 2024-05-12 at 14.21.17.png"]);
@@ -38,7 +95,7 @@ mod tests {
     #[test]
     fn parse_multiple_quoted_properties() {
         let property = r#"x_source abc def "/gfs/cc/clean/012345678911" "17" abc def "Screenshot 2024-05-12 at 14.21.17.png""#;
-        let res = parsing::parse_properties(&property);
+        let res = parsing::parse_properties(property);
         println!("{:?}", res);
         let prop = res.iter().find(|(n, _)| *n == "x_source").unwrap();
         assert_eq!(
@@ -58,14 +115,14 @@ mod tests {
     #[test]
     fn parse_empty_property() {
         let property = "";
-        let res = parsing::parse_properties(&property);
+        let res = parsing::parse_properties(property);
         assert_eq!(res.len(), 0);
     }
 
     #[test]
     fn parse_just_whitespace_property() {
         let property = "     \n  \t  \n  \t  \n";
-        let res = parsing::parse_properties(&property);
+        let res = parsing::parse_properties(property);
         assert_eq!(res.len(), 0);
     }
 }
@@ -0,0 +1,266 @@
+use std::collections::HashSet;
+
+use crate::{
+    element::Element,
+    error::Result,
+    hocr::HOCR,
+    page::{collect_pages, Page},
+    spec_definitions::{elements::OCR_PAGE, properties},
+    HOCRParserError,
+};
+
+/// Options for [`HOCR::merge_with_options`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MergeOptions {
+    /// Merge documents produced by different OCR systems, keeping the `ocr-system` of the
+    /// first document. Default: false.
+    pub allow_conflicting_systems: bool,
+    /// Renumber `ppageno` properties and Tesseract-style element ids (`word_1_3`) by position
+    /// in the merged document. Other ids are kept unless they collide. Default: true.
+    pub renumber: bool,
+}
+
+impl Default for MergeOptions {
+    fn default() -> Self {
+        Self {
+            allow_conflicting_systems: false,
+            renumber: true,
+        }
+    }
+}
+
+fn union(target: &mut Vec<String>, values: &[String]) {
+    for v in values {
+        if !target.contains(v) {
+            target.push(v.clone());
+        }
+    }
+}
+
+fn union_option(target: &mut Option<Vec<String>>, values: &Option<Vec<String>>) {
+    if let Some(values) = values {
+        union(target.get_or_insert_with(Vec::new), values);
+    }
+}
+
+/// Replace the page number in ids following Tesseract's `<kind>_<page>_<n>` scheme, e.g.
+/// `word_1_23` on the third page becomes `word_3_23`, and in page ids like `page_1`. Returns
+/// `None` for other ids.
+fn renumber_id(id: &str, page_number: usize) -> Option<String> {
+    let is_number = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    let is_kind = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_lowercase());
+    match id.split('_').collect::<Vec<_>>()[..] {
+        [kind, page, n] if is_kind(kind) && is_number(page) && is_number(n) => {
+            Some(format!("{kind}_{page_number}_{n}"))
+        }
+        ["page", page] if is_number(page) => Some(format!("page_{page_number}")),
+        _ => None,
+    }
+}
+
+pub(crate) fn unique_id(id: String, seen: &mut HashSet<String>) -> String {
+    let mut unique = id.clone();
+    let mut n = 2;
+    while seen.contains(&unique) {
+        unique = format!("{id}_{n}");
+        n += 1;
+    }
+    seen.insert(unique.clone());
+    unique
+}
+
+struct Renumbering {
+    pages: usize,
+    seen: HashSet<String>,
+}
+
+impl Renumbering {
+    fn element(&mut self, e: &mut Element, page_number: Option<usize>) {
+        let page_number = if e.element_type == OCR_PAGE {
+            self.pages += 1;
+            if let Some((_, values)) = e
+                .properties
+                .iter_mut()
+                .find(|(name, _)| name == properties::PPAGENO)
+            {
+                *values = vec![(self.pages - 1).to_string()];
+            }
+            Some(self.pages)
+        } else {
+            page_number
+        };
+
+        if let Some(id) = e.id.take() {
+            // other ids are only changed if they collide
+            let id = page_number.and_then(|n| renumber_id(&id, n)).unwrap_or(id);
+            e.id = Some(unique_id(id, &mut self.seen));
+        }
+
+        for child in &mut e.children {
+            self.element(child, page_number);
+        }
+    }
+}
+
+impl HOCR {
+    /// Split the document into one document per `ocr_page`, each carrying a copy of the metadata.
+    ///
+    /// Elements outside of pages are dropped.
+    pub fn split_pages(&self) -> Vec<HOCR> {
+        self.pages().map(Page::into_hocr).collect()
+    }
+
+    /// Merge documents into one by concatenating their elements, using the default [`MergeOptions`].
+    ///
+    /// Capabilities, languages and scripts are combined and `ocr-number-of-pages` is recomputed;
+    /// other metadata is taken from the first document defining it. Page numbers and element
+    /// ids are renumbered so that they are unique in the merged document.
+    /// Fails if the documents were produced by different OCR systems.
+    pub fn merge(documents: impl IntoIterator<Item = HOCR>) -> Result<HOCR> {
+        Self::merge_with_options(documents, MergeOptions::default())
+    }
+
+    /// Merge documents into one by concatenating their elements.
+    ///
+    /// See [`HOCR::merge`] and [`MergeOptions`].
+    pub fn merge_with_options(
+        documents: impl IntoIterator<Item = HOCR>,
+        options: MergeOptions,
+    ) -> Result<HOCR> {
+        let mut documents = documents.into_iter();
+        let mut merged = documents.next().ok_or(HOCRParserError::NothingToMerge)?;
+
+        for doc in documents {
+            if doc.system != merged.system && !options.allow_conflicting_systems {
+                return Err(HOCRParserError::ConflictingOCRSystems(
+                    merged.system,
+                    doc.system,
+                ));
+            }
+
+            union(&mut merged.capabilities, &doc.capabilities);
+            union_option(&mut merged.langs, &doc.langs);
+            union_option(&mut merged.scripts, &doc.scripts);
+            for (name, content) in doc.metadata {
+                if !merged.metadata.iter().any(|(n, _)| *n == name) {
+                    merged.metadata.push((name, content));
+                }
+            }
+            merged.elements.extend(doc.elements);
+        }
+
+        if options.renumber {
+            let mut renumbering = Renumbering {
+                pages: 0,
+                seen: HashSet::new(),
+            };
+            for e in &mut merged.elements {
+                renumbering.element(e, None);
+            }
+        }
+
+        let mut pages = vec![];
+        for e in &merged.elements {
+            collect_pages(e, &mut pages);
+        }
+        merged.number_of_pages = Some(pages.len() as u32);
+
+        Ok(merged)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::system_document;
+
+    fn document(system: &str, pages: &[&str]) -> HOCR {
+        let body: String = pages
+            .iter()
+            .map(|id| format!(r#"<div class="ocr_page" id="{id}" title="bbox 0 0 10 10"/>"#))
+            .collect();
+        let meta = r#"<meta name="ocr-langs" content="eng"/>"#;
+        HOCR::from_str(&system_document(system, "ocr_page", meta, &body)).unwrap()
+    }
+
+    #[test]
+    fn split_and_merge() {
+        let doc = document("test", &["page_1", "page_2"]);
+        let pages = doc.split_pages();
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[1].elements[0].id.as_deref(), Some("page_2"));
+        assert_eq!(pages[1].number_of_pages, Some(1));
+
+        let merged = HOCR::merge(pages).unwrap();
+        assert_eq!(merged.number_of_pages, Some(2));
+        assert_eq!(merged.langs, Some(vec!["eng".to_string()]));
+        assert_eq!(merged.elements.len(), 2);
+    }
+
+    #[test]
+    fn merge_rejects_conflicting_systems() {
+        let docs = [document("a", &["p1"]), document("b", &["p1"])];
+        assert!(matches!(
+            HOCR::merge(docs),
+            Err(HOCRParserError::ConflictingOCRSystems(_, _))
+        ));
+        assert!(matches!(
+            HOCR::merge(vec![]),
+            Err(HOCRParserError::NothingToMerge)
+        ));
+    }
+
+    fn page(system: &str, number: usize) -> HOCR {
+        HOCR::from_str(&system_document(
+            system,
+            "ocr_page ocr_line ocrx_word",
+            &format!(r#"<meta name="ocr-langs" content="lang{number}"/>"#),
+            &format!(
+                r#"<div class="ocr_page" id="page_1" title="bbox 0 0 100 100; ppageno 0">
+                    <span class="ocr_line" id="line_1_1">
+                        <span class="ocrx_word" id="word_1_1">w{number}</span>
+                        <span class="ocrx_word" id="custom">x</span>
+                        <span class="ocrx_word" id="fig_2023_a">y</span>
+                    </span>
+                </div>"#
+            ),
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn merge_renumbers_pages_and_ids() {
+        let merged = HOCR::merge([page("test", 1), page("test", 2), page("test", 3)]).unwrap();
+        assert_eq!(merged.number_of_pages, Some(3));
+        assert_eq!(
+            merged.langs,
+            Some(vec!["lang1".into(), "lang2".into(), "lang3".into()])
+        );
+
+        let third = &merged.elements[2];
+        assert_eq!(third.id.as_deref(), Some("page_3"));
+        assert_eq!(third.property("ppageno"), Some(&["2".to_string()][..]));
+        let ids: Vec<_> = third.children[0]
+            .children
+            .iter()
+            .map(|w| w.id.as_deref().unwrap())
+            .collect();
+        assert_eq!(ids, ["word_3_1", "custom_3", "fig_2023_a_3"]);
+
+        let split = merged.split_pages();
+        assert_eq!(split.len(), 3);
+        assert_eq!(split[1].plain_text().trim(), "w2 x y");
+    }
+
+    #[test]
+    fn merge_allows_conflicting_systems() {
+        let documents = [page("a", 1), page("b", 2)];
+        let options = MergeOptions {
+            allow_conflicting_systems: true,
+            ..MergeOptions::default()
+        };
+        let merged = HOCR::merge_with_options(documents, options).unwrap();
+        assert_eq!(merged.system, "a");
+        assert_eq!(merged.number_of_pages, Some(2));
+    }
+}
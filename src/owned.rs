@@ -0,0 +1,146 @@
+use roxmltree::{Document, ParsingOptions};
+use self_cell::self_cell;
+
+use crate::{
+    compression::decompress, encoding::decode, error::Result, hocr::HOCRBorrowed, view::HOCRView,
+    HOCR,
+};
+
+self_cell!(
+    struct OwnedDocument {
+        owner: String,
+
+        #[covariant]
+        dependent: Document,
+    }
+
+    impl {Debug}
+);
+
+self_cell!(
+    struct OwnedHOCR {
+        owner: OwnedDocument,
+
+        #[covariant]
+        dependent: HOCRBorrowed,
+    }
+
+    impl {Debug}
+);
+
+/// A parsed hOCR document that owns its source string.
+///
+/// [`HOCRBorrowed`] borrows from a [`roxmltree::Document`], which in turn borrows from the
+/// source string, so all three have to be kept in scope together. [`HOCROwned`] bundles them
+/// into a single value that can be returned from functions, stored in structs and sent across
+/// threads, while still giving access to the borrowed representation without copying.
+///
+/// ```
+/// use hocr_parser::HOCROwned;
+///
+/// fn load(path: &str) -> hocr_parser::Result<HOCROwned> {
+///     HOCROwned::new(std::fs::read_to_string(path)?)
+/// }
+///
+/// let hocr = load("examples/data/paper-image.hocr").unwrap();
+/// assert_eq!(hocr.borrowed().system, "tesseract 5.3.4");
+/// ```
+#[derive(Debug)]
+pub struct HOCROwned {
+    inner: OwnedHOCR,
+}
+
+impl HOCROwned {
+    /// Create a new [`HOCROwned`] instance by parsing `source`.
+    pub fn new<S: Into<String>>(source: S) -> Result<Self> {
+        let options = ParsingOptions {
+            allow_dtd: true,
+            ..ParsingOptions::default()
+        };
+        let document = OwnedDocument::try_new(source.into(), |source| {
+            Document::parse_with_options(source, options)
+        })?;
+        let inner = OwnedHOCR::try_new(document, |document| {
+            HOCRBorrowed::new_from_document(document.borrow_dependent())
+        })?;
+        Ok(Self { inner })
+    }
+
+    /// Create a new [`HOCROwned`] instance from hOCR bytes.
+    ///
+    /// Compression and encoding are detected as for [`HOCR::from_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let bytes = decompress(bytes)?;
+        Self::new(decode(&bytes)?)
+    }
+
+    /// The source string the document was parsed from.
+    pub fn source(&self) -> &str {
+        self.inner.borrow_owner().borrow_owner()
+    }
+
+    /// The parsed XML document.
+    pub fn document(&self) -> &Document<'_> {
+        self.inner.borrow_owner().borrow_dependent()
+    }
+
+    /// The borrowed representation of the document.
+    pub fn borrowed(&self) -> &HOCRBorrowed<'_> {
+        self.inner.borrow_dependent()
+    }
+
+    /// Returns a lazy view of the document, see [`HOCRView`].
+    pub fn view(&self) -> HOCRView<'_, '_> {
+        // the document has already been checked when creating the borrowed representation
+        HOCRView::new(self.document()).expect("document was checked on creation")
+    }
+
+    /// Convert to an owned [`HOCR`] instance.
+    pub fn to_hocr(&self) -> HOCR {
+        HOCR::from_hocr_borrowed(self.borrowed().clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::document;
+
+    #[test]
+    fn owned_document_can_be_sent() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<HOCROwned>();
+
+        let xml = std::fs::read_to_string("examples/data/paper-image.hocr").unwrap();
+        let expected = HOCR::from_str(&xml).unwrap();
+        let owned = HOCROwned::new(xml).unwrap();
+
+        let hocr = std::thread::spawn(move || owned.to_hocr()).join().unwrap();
+        assert_eq!(hocr, expected);
+    }
+
+    #[test]
+    fn owned_document_accessors() {
+        let xml = document("ocrx_word", r#"<span class="ocrx_word" id="w1">a</span>"#);
+        let owned = HOCROwned::from_bytes(xml.as_bytes()).unwrap();
+
+        assert!(owned.source().starts_with("<html>"));
+        assert_eq!(owned.borrowed().elements[0].id, Some("w1"));
+        assert_eq!(owned.view().iter().count(), 1);
+        assert!(HOCROwned::new("<html/>").is_err());
+    }
+
+    #[test]
+    fn view_skips_meta_without_content() {
+        // the first `ocr-system` tag has no content, which both parsers skip
+        let xml = r#"<html><head>
+            <meta name="ocr-system"/>
+            <meta name="ocr-system" content="t"/>
+            <meta name="ocr-capabilities" content="ocrx_word"/>
+            </head><body><span class="ocrx_word" id="w1">a</span></body></html>"#;
+        let owned = HOCROwned::new(xml).unwrap();
+
+        assert_eq!(owned.view().system(), "t");
+        assert_eq!(owned.borrowed().system, "t");
+    }
+}
@@ -0,0 +1,189 @@
+use std::io::Read;
+
+use crate::{
+    element::Element,
+    error::Result,
+    geometry::BBox,
+    hocr::{DocumentMetadata, HOCR},
+    spec_definitions::{elements::OCR_PAGE, properties},
+};
+
+pub(crate) fn collect_pages<'a>(e: &'a Element, pages: &mut Vec<&'a Element>) {
+    if e.element_type == OCR_PAGE {
+        pages.push(e);
+    } else {
+        for child in &e.children {
+            collect_pages(child, pages);
+        }
+    }
+}
+
+fn take_pages(e: Element, pages: &mut Vec<Element>) {
+    if e.element_type == OCR_PAGE {
+        pages.push(e);
+    } else {
+        for child in e.children {
+            take_pages(child, pages);
+        }
+    }
+}
+
+/// A single `ocr_page` element together with the metadata of its document.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Page {
+    pub metadata: DocumentMetadata,
+    /// Physical page number from the `ppageno` property.
+    pub ppageno: Option<u32>,
+    /// Path or URL of the page image from the `image` property.
+    pub image: Option<String>,
+    /// MD5 checksum of the page image from the `imagemd5` property.
+    pub imagemd5: Option<String>,
+    pub bbox: Option<BBox>,
+    pub element: Element,
+}
+
+impl Page {
+    /// Create a new [`Page`] instance from an `ocr_page` element and its document metadata.
+    pub fn new(element: Element, metadata: DocumentMetadata) -> Self {
+        let single = |name| match element.property(name) {
+            Some([value]) => Some(value.clone()),
+            _ => None,
+        };
+
+        Self {
+            ppageno: single(properties::PPAGENO).and_then(|v| v.parse().ok()),
+            image: single(properties::IMAGE),
+            imagemd5: single(properties::IMAGEMD5),
+            bbox: element.bbox(),
+            metadata,
+            element,
+        }
+    }
+
+    /// Returns the page as a single-page document.
+    pub fn into_hocr(self) -> HOCR {
+        let metadata = DocumentMetadata {
+            number_of_pages: Some(1),
+            ..self.metadata
+        };
+        HOCR::from_parts(metadata, vec![self.element])
+    }
+}
+
+/// Iterator over the pages of a document, created by [`HOCR::into_pages`] and [`HOCR::read_pages`].
+pub struct IntoPages {
+    metadata: DocumentMetadata,
+    pages: std::vec::IntoIter<Element>,
+}
+
+impl IntoPages {
+    /// The metadata of the document the pages belong to.
+    pub fn metadata(&self) -> &DocumentMetadata {
+        &self.metadata
+    }
+}
+
+impl Iterator for IntoPages {
+    type Item = Page;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let element = self.pages.next()?;
+        Some(Page::new(element, self.metadata.clone()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.pages.size_hint()
+    }
+}
+
+impl ExactSizeIterator for IntoPages {}
+
+impl HOCR {
+    /// Returns an iterator over copies of the `ocr_page` elements of the document, in document
+    /// order, each with the document metadata attached.
+    pub fn pages(&self) -> impl Iterator<Item = Page> + '_ {
+        let mut pages = vec![];
+        for e in &self.elements {
+            collect_pages(e, &mut pages);
+        }
+
+        let metadata = self.document_metadata();
+        pages
+            .into_iter()
+            .map(move |page| Page::new(page.clone(), metadata.clone()))
+    }
+
+    /// Consume the document and return an iterator over its `ocr_page` elements.
+    ///
+    /// Elements outside of pages are dropped, as is each page once the iterator has yielded it.
+    pub fn into_pages(self) -> IntoPages {
+        let metadata = self.document_metadata();
+        let mut pages = vec![];
+        for e in self.elements {
+            take_pages(e, &mut pages);
+        }
+
+        IntoPages {
+            metadata,
+            pages: pages.into_iter(),
+        }
+    }
+
+    /// Read a hOCR document from `reader` and return an iterator over its pages.
+    ///
+    /// The encoding is determined as for [`HOCR::from_bytes`]. The XML tree is dropped before
+    /// the first page is returned; for files that do not fit in memory, use the streaming parser
+    /// of the `stream` feature instead.
+    pub fn read_pages<R: Read>(reader: R) -> Result<IntoPages> {
+        Ok(HOCR::from_reader(reader)?.into_pages())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::document_with_meta;
+
+    fn doc() -> String {
+        document_with_meta(
+            "ocr_page ocrx_word",
+            r#"<meta name="ocr-number-of-pages" content="2"/>"#,
+            r#"<div class="ocr_page" id="page_1" title='image "scans/p 1.png"; imagemd5 "abc"; bbox 0 0 100 200; ppageno 0'>
+                <span class="ocrx_word" id="w1">a</span>
+            </div>
+            <div class="ocr_page" id="page_2" title="bbox 0 0 100 200; ppageno 1">
+                <span class="ocrx_word" id="w2">b</span>
+            </div>"#,
+        )
+    }
+
+    #[test]
+    fn page_properties() {
+        let hocr = HOCR::from_str(&doc()).unwrap();
+        let pages: Vec<_> = hocr.pages().collect();
+
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].ppageno, Some(0));
+        assert_eq!(pages[0].image.as_deref(), Some("scans/p 1.png"));
+        assert_eq!(pages[0].imagemd5.as_deref(), Some("abc"));
+        assert_eq!(pages[0].bbox, Some(BBox::new(0, 0, 100, 200)));
+        assert_eq!(pages[1].ppageno, Some(1));
+        assert_eq!(pages[1].image, None);
+        assert_eq!(pages[1].metadata.system, "test");
+
+        let owned: Vec<_> = hocr.clone().into_pages().collect();
+        assert_eq!(owned, pages);
+    }
+
+    #[test]
+    fn read_pages_from_reader() {
+        let pages = HOCR::read_pages(doc().as_bytes()).unwrap();
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages.metadata().number_of_pages, Some(2));
+
+        let hocr = pages.last().unwrap().into_hocr();
+        assert_eq!(hocr.number_of_pages, Some(1));
+        assert_eq!(hocr.plain_text().trim(), "b");
+    }
+}
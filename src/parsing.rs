@@ -1,35 +1,105 @@
-use crate::spec_definitions::HOCR_PROPERTIES;
+use crate::spec_definitions::{elements::OCRX_WORD, HOCR_ELEMENTS, HOCR_PROPERTIES};
 
-pub(crate) fn parse_properties(prop: &str) -> Vec<(&str, Vec<&str>)> {
-    prop.split(';')
-        .filter_map(|p| {
-            let mut iter = p.split_whitespace();
-            let property_name = iter.next()?;
+/// Iterator over the values of a single property, see [`Properties`].
+///
+/// Quoted values are returned whole, unquoted values are split at whitespace.
+#[derive(Debug, Clone)]
+pub struct Values<'a> {
+    segments: std::str::Split<'a, char>,
+    words: Option<std::str::SplitWhitespace<'a>>,
+}
 
-            let values = p.trim().strip_prefix(property_name)?;
+impl<'a> Values<'a> {
+    fn new(values: &'a str) -> Self {
+        Self {
+            segments: values.split('"'),
+            words: None,
+        }
+    }
+}
+
+impl<'a> Iterator for Values<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(word) = self.words.as_mut().and_then(Iterator::next) {
+                return Some(word);
+            }
+            self.words = None;
 
-            let property_values: Vec<&str> = values
-                .split('"')
-                .flat_map(|s| {
-                    let is_just_whitespace = s.chars().all(char::is_whitespace);
+            let segment = self.segments.next()?;
+            if segment.chars().all(char::is_whitespace) {
+                continue;
+            }
+            if segment.trim() != segment {
+                self.words = Some(segment.split_whitespace());
+                continue;
+            }
+            return Some(segment);
+        }
+    }
+}
 
-                    if is_just_whitespace {
-                        return vec![];
-                    }
+/// Iterator over the properties of a `title` attribute, yielding each property name with an
+/// iterator over its values. Nothing is allocated.
+#[derive(Debug, Clone)]
+pub struct Properties<'a> {
+    properties: std::str::Split<'a, char>,
+}
 
-                    if s.trim() != s {
-                        return s.split_whitespace().collect();
-                    }
+impl<'a> Properties<'a> {
+    /// Create a new [`Properties`] iterator over the content of a `title` attribute.
+    pub fn new(title: &'a str) -> Self {
+        Self {
+            properties: title.split(';'),
+        }
+    }
+}
 
-                    vec![s]
-                })
-                .collect();
+impl<'a> Iterator for Properties<'a> {
+    type Item = (&'a str, Values<'a>);
 
-            Some((property_name, property_values))
+    fn next(&mut self) -> Option<Self::Item> {
+        self.properties.find_map(|p| {
+            let property_name = p.split_whitespace().next()?;
+            let values = p.trim().strip_prefix(property_name)?;
+            Some((property_name, Values::new(values)))
         })
+    }
+}
+
+pub(crate) fn parse_properties(prop: &str) -> Vec<(&str, Vec<&str>)> {
+    Properties::new(prop)
+        .map(|(name, values)| (name, values.collect()))
         .collect()
 }
 
 pub(crate) fn check_property_name(prop: &str) -> bool {
     HOCR_PROPERTIES.contains(&prop) || prop.starts_with("x_")
-}
\ No newline at end of file
+}
+
+pub(crate) fn check_element_name(element_type: &str) -> bool {
+    HOCR_ELEMENTS.contains(&element_type) || element_type.starts_with("ocrx_")
+}
+
+/// Tags accepted without a class for inline formatting, as Tesseract emits `<strong>` and
+/// `<em>` inside bold and italic words.
+pub(crate) const FORMATTING_TAGS: [&str; 4] = ["strong", "em", "b", "i"];
+
+/// Whether an element is an unclassed inline formatting element such as `<strong>`.
+pub(crate) fn is_formatting_element(element_type: &str, tag_name: &str) -> bool {
+    element_type.is_empty() && FORMATTING_TAGS.contains(&tag_name)
+}
+
+/// Whether the children of an element may be formatting elements: it is a word, or a
+/// formatting element inside one.
+pub(crate) fn allows_formatting(element_type: &str, tag_name: &str) -> bool {
+    element_type == OCRX_WORD || is_formatting_element(element_type, tag_name)
+}
+
+/// Whether an element with the given class and tag is accepted by the parsers, where `in_word`
+/// tells whether its parent [`allows_formatting`].
+pub(crate) fn check_element(element_type: &str, tag_name: &str, in_word: bool) -> bool {
+    check_element_name(element_type) || (in_word && is_formatting_element(element_type, tag_name))
+}
@@ -0,0 +1,322 @@
+use crate::{
+    element::{Element, ElementBorrowed},
+    geometry::{BBox, Transform},
+    traits::HocrElement,
+};
+
+/// A polygon in image pixel coordinates, as used by the `poly` property for curved or rotated
+/// regions.
+///
+/// The points are the vertices in order; the polygon is implicitly closed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Polygon {
+    pub points: Vec<(i32, i32)>,
+}
+
+/// Signed area and centroid of a polygon by the shoelace formula.
+fn signed_area_centroid(points: &[(f64, f64)]) -> (f64, (f64, f64)) {
+    let (mut area, mut cx, mut cy) = (0.0, 0.0, 0.0);
+    for (i, &(x0, y0)) in points.iter().enumerate() {
+        let (x1, y1) = points[(i + 1) % points.len()];
+        let cross = x0 * y1 - x1 * y0;
+        area += cross;
+        cx += (x0 + x1) * cross;
+        cy += (y0 + y1) * cross;
+    }
+    area /= 2.0;
+    (area, (cx / (6.0 * area), cy / (6.0 * area)))
+}
+
+/// Clip a polygon to the half-plane where `inside` holds, with `cut` computing the point where
+/// an edge crosses its border (Sutherland–Hodgman).
+fn clip(
+    points: Vec<(f64, f64)>,
+    inside: impl Fn((f64, f64)) -> bool,
+    cut: impl Fn((f64, f64), (f64, f64)) -> (f64, f64),
+) -> Vec<(f64, f64)> {
+    let mut clipped = Vec::with_capacity(points.len() + 1);
+    for (i, &current) in points.iter().enumerate() {
+        let previous = points[(i + points.len() - 1) % points.len()];
+        match (inside(previous), inside(current)) {
+            (true, true) => clipped.push(current),
+            (true, false) => clipped.push(cut(previous, current)),
+            (false, true) => {
+                clipped.push(cut(previous, current));
+                clipped.push(current);
+            }
+            (false, false) => {}
+        }
+    }
+    clipped
+}
+
+/// Distance of `p` from the line through `a` and `b`.
+fn line_distance(p: (i32, i32), a: (i32, i32), b: (i32, i32)) -> f64 {
+    let (dx, dy) = ((b.0 - a.0) as f64, (b.1 - a.1) as f64);
+    let length = dx.hypot(dy);
+    if length == 0.0 {
+        return ((p.0 - a.0) as f64).hypot((p.1 - a.1) as f64);
+    }
+    (dy * (p.0 - a.0) as f64 - dx * (p.1 - a.1) as f64).abs() / length
+}
+
+/// Douglas–Peucker simplification of an open polyline, keeping both ends.
+fn simplify_polyline(points: &[(i32, i32)], tolerance: f64, kept: &mut Vec<(i32, i32)>) {
+    let (first, last) = (points[0], points[points.len() - 1]);
+    let farthest = (1..points.len() - 1)
+        .map(|i| (i, line_distance(points[i], first, last)))
+        .max_by(|a, b| a.1.total_cmp(&b.1));
+
+    match farthest {
+        Some((i, distance)) if distance > tolerance => {
+            simplify_polyline(&points[..=i], tolerance, kept);
+            kept.pop();
+            simplify_polyline(&points[i..], tolerance, kept);
+        }
+        _ => kept.extend([first, last]),
+    }
+}
+
+impl Polygon {
+    /// Create a new [`Polygon`] from its vertices.
+    pub fn new(points: Vec<(i32, i32)>) -> Self {
+        Self { points }
+    }
+
+    /// The rectangle covered by `b`, with its corners in clockwise order on the page.
+    pub fn from_bbox(b: &BBox) -> Self {
+        Self::new(vec![(b.x0, b.y0), (b.x1, b.y0), (b.x1, b.y1), (b.x0, b.y1)])
+    }
+
+    /// Parse a [`Polygon`] from property values, two per point, with at least three points.
+    pub fn from_values<S: AsRef<str>>(values: &[S]) -> Option<Self> {
+        if values.len() < 6 || values.len() % 2 != 0 {
+            return None;
+        }
+
+        let points = values
+            .chunks(2)
+            .map(|p| Some((p[0].as_ref().parse().ok()?, p[1].as_ref().parse().ok()?)))
+            .collect::<Option<_>>()?;
+        Some(Self::new(points))
+    }
+
+    /// The property values, two per point.
+    pub fn values(&self) -> Vec<String> {
+        self.points
+            .iter()
+            .flat_map(|&(x, y)| [x, y])
+            .map(|v| v.to_string())
+            .collect()
+    }
+
+    fn to_f64(&self) -> Vec<(f64, f64)> {
+        self.points
+            .iter()
+            .map(|&(x, y)| (x as f64, y as f64))
+            .collect()
+    }
+
+    /// The area enclosed by the polygon in square pixels.
+    pub fn area(&self) -> f64 {
+        if self.points.is_empty() {
+            return 0.0;
+        }
+        signed_area_centroid(&self.to_f64()).0.abs()
+    }
+
+    /// The center of mass of the enclosed area, or the mean of the vertices if the polygon
+    /// encloses no area. Returns `None` for a polygon without points.
+    pub fn centroid(&self) -> Option<(f64, f64)> {
+        let points = self.to_f64();
+        let n = points.len() as f64;
+        match signed_area_centroid(&points) {
+            _ if points.is_empty() => None,
+            (area, centroid) if area != 0.0 => Some(centroid),
+            _ => Some((
+                points.iter().map(|p| p.0).sum::<f64>() / n,
+                points.iter().map(|p| p.1).sum::<f64>() / n,
+            )),
+        }
+    }
+
+    /// The smallest box containing all points. Returns `None` for a polygon without points.
+    pub fn bbox(&self) -> Option<BBox> {
+        self.points
+            .iter()
+            .map(|&(x, y)| BBox::new(x, y, x, y))
+            .reduce(|a, b| a.union(&b))
+    }
+
+    /// Whether the point lies inside the polygon, by the even-odd rule.
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        let points = self.to_f64();
+        let mut inside = false;
+        for (i, &(x0, y0)) in points.iter().enumerate() {
+            let (x1, y1) = points[(i + 1) % points.len()];
+            if (y0 > y) != (y1 > y) && x < x0 + (y - y0) / (y1 - y0) * (x1 - x0) {
+                inside = !inside;
+            }
+        }
+        inside
+    }
+
+    /// The area of the intersection of the polygon with `b` in square pixels.
+    pub fn intersection_area(&self, b: &BBox) -> f64 {
+        let b = b.normalized();
+        let (x0, y0, x1, y1) = (b.x0 as f64, b.y0 as f64, b.x1 as f64, b.y1 as f64);
+        let at_x = |x: f64| {
+            move |(ax, ay): (f64, f64), (bx, by): (f64, f64)| {
+                (x, ay + (by - ay) * (x - ax) / (bx - ax))
+            }
+        };
+        let at_y = |y: f64| {
+            move |(ax, ay): (f64, f64), (bx, by): (f64, f64)| {
+                (ax + (bx - ax) * (y - ay) / (by - ay), y)
+            }
+        };
+
+        let mut points = self.to_f64();
+        points = clip(points, |p| p.0 >= x0, at_x(x0));
+        points = clip(points, |p| p.0 <= x1, at_x(x1));
+        points = clip(points, |p| p.1 >= y0, at_y(y0));
+        points = clip(points, |p| p.1 <= y1, at_y(y1));
+        if points.is_empty() {
+            return 0.0;
+        }
+        signed_area_centroid(&points).0.abs()
+    }
+
+    /// Whether the polygon and `b` overlap in a region of positive area.
+    pub fn intersects(&self, b: &BBox) -> bool {
+        self.intersection_area(b) > 0.0
+    }
+
+    /// Remove vertices that lie within `tolerance` pixels of the line through their remaining
+    /// neighbours (Douglas–Peucker). The result keeps at least three points.
+    pub fn simplify(&self, tolerance: f64) -> Polygon {
+        if self.points.len() <= 3 {
+            return self.clone();
+        }
+
+        // split the closed ring at the first point and the point farthest from it
+        let first = self.points[0];
+        let far = (1..self.points.len())
+            .max_by_key(|&i| {
+                let (x, y) = self.points[i];
+                (x as i64 - first.0 as i64).pow(2) + (y as i64 - first.1 as i64).pow(2)
+            })
+            .expect("more than three points");
+        let mut ring = self.points.clone();
+        ring.push(first);
+
+        let mut kept = vec![];
+        simplify_polyline(&ring[..=far], tolerance, &mut kept);
+        kept.pop();
+        simplify_polyline(&ring[far..], tolerance, &mut kept);
+        kept.pop();
+
+        if kept.len() < 3 {
+            return self.clone();
+        }
+        Polygon::new(kept)
+    }
+
+    /// The polygon with every point mapped by `t` and rounded to whole pixels.
+    pub fn transform(&self, t: &Transform) -> Polygon {
+        let points = self
+            .points
+            .iter()
+            .map(|&(x, y)| {
+                let (x, y) = t.apply(x as f64, y as f64);
+                (x.round() as i32, y.round() as i32)
+            })
+            .collect();
+        Polygon::new(points)
+    }
+}
+
+impl<'a> ElementBorrowed<'a> {
+    /// Returns the parsed `poly` property, if present and well-formed.
+    pub fn poly(&self) -> Option<Polygon> {
+        HocrElement::poly(self)
+    }
+
+    /// Returns the `poly` of this element, or its `bbox` as a rectangle.
+    pub fn shape(&self) -> Option<Polygon> {
+        HocrElement::shape(self)
+    }
+}
+
+impl Element {
+    /// Returns the parsed `poly` property, if present and well-formed.
+    pub fn poly(&self) -> Option<Polygon> {
+        HocrElement::poly(self)
+    }
+
+    /// Returns the `poly` of this element, or its `bbox` as a rectangle.
+    pub fn shape(&self) -> Option<Polygon> {
+        HocrElement::shape(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::document;
+
+    fn triangle() -> Polygon {
+        Polygon::new(vec![(0, 0), (100, 0), (0, 100)])
+    }
+
+    #[test]
+    fn measure_polygons() {
+        let p = triangle();
+        assert_eq!(p.area(), 5000.0);
+        let (cx, cy) = p.centroid().unwrap();
+        assert!((cx - 100.0 / 3.0).abs() < 1e-9 && (cy - 100.0 / 3.0).abs() < 1e-9);
+        assert_eq!(p.bbox(), Some(BBox::new(0, 0, 100, 100)));
+        assert!(p.contains(10.0, 10.0));
+        assert!(!p.contains(60.0, 60.0));
+
+        assert_eq!(p.intersection_area(&BBox::new(0, 0, 50, 50)), 2500.0);
+        assert_eq!(p.intersection_area(&BBox::new(50, 50, 100, 100)), 0.0);
+        assert!(!p.intersects(&BBox::new(60, 60, 100, 100)));
+        assert_eq!(Polygon::from_bbox(&BBox::new(0, 0, 10, 20)).area(), 200.0);
+    }
+
+    #[test]
+    fn simplify_polygon() {
+        let p = Polygon::new(vec![
+            (0, 0),
+            (50, 1),
+            (100, 0),
+            (100, 100),
+            (0, 100),
+            (0, 50),
+        ]);
+        assert_eq!(
+            p.simplify(2.0).points,
+            [(0, 0), (100, 0), (100, 100), (0, 100)]
+        );
+        assert_eq!(p.simplify(0.5).points.len(), 5);
+    }
+
+    #[test]
+    fn shape_falls_back_to_bbox() {
+        let xml = document(
+            "ocr_page ocr_carea",
+            r#"<div class="ocr_page" id="page_1" title="bbox 0 0 500 500">
+                <div class="ocr_carea" id="a" title="bbox 0 0 100 100; poly 0 0 100 0 0 100"></div>
+                <div class="ocr_carea" id="b" title="bbox 0 0 10 20"></div>
+            </div>"#,
+        );
+        let hocr = crate::HOCR::from_str(&xml).unwrap();
+        let [a, b] = &hocr.elements[0].children[..] else {
+            panic!("two blocks expected");
+        };
+        assert_eq!(a.shape(), Some(triangle()));
+        assert_eq!(b.poly(), None);
+        assert_eq!(b.shape().unwrap().area(), 200.0);
+    }
+}
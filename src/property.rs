@@ -0,0 +1,345 @@
+use crate::{
+    baseline::Baseline, element::Element, geometry::BBox, polygon::Polygon,
+    spec_definitions::properties,
+};
+
+/// A typed hOCR property.
+///
+/// Properties with a value that does not parse, or that would not be written back exactly as it
+/// was read (e.g. `x_wconf 95.0`), are kept verbatim as [`Property::Other`] so that converting
+/// between raw and typed properties never loses information.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "repr::PropertyRepr", into = "repr::PropertyRepr")
+)]
+pub enum Property {
+    /// `bbox x0 y0 x1 y1`
+    BBox(BBox),
+    /// `baseline slope offset`
+    Baseline(Baseline),
+    /// `cflow name`
+    Cflow(String),
+    /// `image "path"`
+    Image(String),
+    /// `imagemd5 "checksum"`
+    ImageMd5(String),
+    /// `lpageno value`
+    LPageNo(String),
+    /// `poly x1 y1 x2 y2 ...`
+    Poly(Polygon),
+    /// `ppageno n`
+    PPageNo(u32),
+    /// `order n`
+    Order(u32),
+    /// `scan_res x_dpi y_dpi`
+    ScanRes(f64, f64),
+    /// `textangle degrees`
+    TextAngle(f64),
+    /// `x_bboxes x0 y0 x1 y1 ...`
+    XBBoxes(Vec<BBox>),
+    /// `x_confs c1 c2 ...`
+    XConfs(Vec<f64>),
+    /// `x_font "name"`
+    XFont(String),
+    /// `x_fsize size`
+    XFsize(f64),
+    /// `x_wconf confidence`
+    XWconf(f64),
+    /// Any other property, or a property whose values could not be represented exactly.
+    Other { name: String, values: Vec<String> },
+}
+
+fn single<S: AsRef<str>>(values: &[S]) -> Option<&str> {
+    match values {
+        [v] => Some(v.as_ref()),
+        _ => None,
+    }
+}
+
+fn number<T: std::str::FromStr, S: AsRef<str>>(values: &[S]) -> Option<T> {
+    single(values)?.parse().ok()
+}
+
+fn numbers<S: AsRef<str>>(values: &[S]) -> Option<Vec<f64>> {
+    values.iter().map(|v| v.as_ref().parse().ok()).collect()
+}
+
+impl Property {
+    /// Parse a property from its name and raw values.
+    pub fn parse<S: AsRef<str>>(name: &str, values: &[S]) -> Property {
+        let typed = match name {
+            properties::BBOX => BBox::from_values(values).map(Property::BBox),
+            properties::BASELINE => Baseline::from_values(values).map(Property::Baseline),
+            properties::CFLOW => single(values).map(|v| Property::Cflow(v.to_string())),
+            properties::IMAGE => single(values).map(|v| Property::Image(v.to_string())),
+            properties::IMAGEMD5 => single(values).map(|v| Property::ImageMd5(v.to_string())),
+            properties::LPAGENO => single(values).map(|v| Property::LPageNo(v.to_string())),
+            properties::POLY => Polygon::from_values(values).map(Property::Poly),
+            properties::PPAGENO => number(values).map(Property::PPageNo),
+            properties::ORDER => number(values).map(Property::Order),
+            properties::SCAN_RES => match numbers(values).as_deref() {
+                Some(&[x, y]) => Some(Property::ScanRes(x, y)),
+                _ => None,
+            },
+            properties::TEXTANGLE => number(values).map(Property::TextAngle),
+            properties::X_BBOXES => BBox::list_from_values(values).map(Property::XBBoxes),
+            properties::X_CONFS => numbers(values).map(Property::XConfs),
+            properties::X_FONT => single(values).map(|v| Property::XFont(v.to_string())),
+            properties::X_FSIZE => number(values).map(Property::XFsize),
+            properties::X_WCONF => number(values).map(Property::XWconf),
+            _ => None,
+        };
+
+        match typed {
+            Some(p)
+                if p.values()
+                    .iter()
+                    .map(String::as_str)
+                    .eq(values.iter().map(AsRef::as_ref)) =>
+            {
+                p
+            }
+            _ => Property::Other {
+                name: name.to_string(),
+                values: values.iter().map(|v| v.as_ref().to_string()).collect(),
+            },
+        }
+    }
+
+    /// The property name as it appears in the `title` attribute.
+    pub fn name(&self) -> &str {
+        match self {
+            Property::BBox(_) => properties::BBOX,
+            Property::Baseline(_) => properties::BASELINE,
+            Property::Cflow(_) => properties::CFLOW,
+            Property::Image(_) => properties::IMAGE,
+            Property::ImageMd5(_) => properties::IMAGEMD5,
+            Property::LPageNo(_) => properties::LPAGENO,
+            Property::Poly(_) => properties::POLY,
+            Property::PPageNo(_) => properties::PPAGENO,
+            Property::Order(_) => properties::ORDER,
+            Property::ScanRes(..) => properties::SCAN_RES,
+            Property::TextAngle(_) => properties::TEXTANGLE,
+            Property::XBBoxes(_) => properties::X_BBOXES,
+            Property::XConfs(_) => properties::X_CONFS,
+            Property::XFont(_) => properties::X_FONT,
+            Property::XFsize(_) => properties::X_FSIZE,
+            Property::XWconf(_) => properties::X_WCONF,
+            Property::Other { name, .. } => name,
+        }
+    }
+
+    /// The raw property values as they appear in the `title` attribute.
+    pub fn values(&self) -> Vec<String> {
+        match self {
+            Property::BBox(b) => [b.x0, b.y0, b.x1, b.y1]
+                .iter()
+                .map(i32::to_string)
+                .collect(),
+            Property::Baseline(b) => vec![b.slope.to_string(), b.offset.to_string()],
+            Property::Cflow(v)
+            | Property::Image(v)
+            | Property::ImageMd5(v)
+            | Property::LPageNo(v)
+            | Property::XFont(v) => vec![v.clone()],
+            Property::Poly(p) => p.values(),
+            Property::PPageNo(n) | Property::Order(n) => vec![n.to_string()],
+            Property::ScanRes(x, y) => vec![x.to_string(), y.to_string()],
+            Property::TextAngle(v) | Property::XFsize(v) | Property::XWconf(v) => {
+                vec![v.to_string()]
+            }
+            Property::XBBoxes(boxes) => boxes
+                .iter()
+                .flat_map(|b| [b.x0, b.y0, b.x1, b.y1])
+                .map(|v| v.to_string())
+                .collect(),
+            Property::XConfs(confs) => confs.iter().map(f64::to_string).collect(),
+            Property::Other { values, .. } => values.clone(),
+        }
+    }
+
+    /// Convert to the raw `(name, values)` pair stored in [`Element::properties`].
+    pub fn to_raw(&self) -> (String, Vec<String>) {
+        (self.name().to_string(), self.values())
+    }
+}
+
+impl Element {
+    /// Returns the properties of this element parsed into [`Property`] values.
+    pub fn typed_properties(&self) -> Vec<Property> {
+        self.properties
+            .iter()
+            .map(|(name, values)| Property::parse(name, values))
+            .collect()
+    }
+}
+
+#[cfg(feature = "serde")]
+pub(crate) mod repr {
+    //! JSON representation of [`Property`]: `{"name": "bbox", "value": ...}` for typed
+    //! properties and `{"name": "x_size", "values": ["21.25"]}` for all others.
+
+    use super::Property;
+    use crate::{baseline::Baseline, geometry::BBox, polygon::Polygon};
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+    #[serde(untagged)]
+    pub enum PropertyRepr {
+        Typed(TypedProperty),
+        Other { name: String, values: Vec<String> },
+    }
+
+    /// Baseline as `slope` and `offset` relative to the bottom-left corner of the bounding box.
+    #[derive(serde::Serialize, serde::Deserialize)]
+    #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+    pub struct BaselineRepr {
+        slope: f64,
+        offset: f64,
+    }
+
+    /// Horizontal and vertical scan resolution in dots per inch.
+    #[derive(serde::Serialize, serde::Deserialize)]
+    #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+    pub struct ScanResRepr {
+        x: f64,
+        y: f64,
+    }
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    #[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+    #[serde(tag = "name", content = "value", rename_all = "snake_case")]
+    pub enum TypedProperty {
+        #[serde(rename = "bbox")]
+        BBox(BBox),
+        Baseline(BaselineRepr),
+        Cflow(String),
+        Image(String),
+        #[serde(rename = "imagemd5")]
+        ImageMd5(String),
+        #[serde(rename = "lpageno")]
+        LPageNo(String),
+        /// Vertices as `[x, y]` pairs.
+        Poly(Vec<(i32, i32)>),
+        #[serde(rename = "ppageno")]
+        PPageNo(u32),
+        Order(u32),
+        ScanRes(ScanResRepr),
+        Textangle(f64),
+        #[serde(rename = "x_bboxes")]
+        XBBoxes(Vec<BBox>),
+        XConfs(Vec<f64>),
+        XFont(String),
+        XFsize(f64),
+        XWconf(f64),
+    }
+
+    impl From<Property> for PropertyRepr {
+        fn from(p: Property) -> Self {
+            use TypedProperty as T;
+
+            PropertyRepr::Typed(match p {
+                Property::BBox(b) => T::BBox(b),
+                Property::Baseline(Baseline { slope, offset }) => {
+                    T::Baseline(BaselineRepr { slope, offset })
+                }
+                Property::Cflow(v) => T::Cflow(v),
+                Property::Image(v) => T::Image(v),
+                Property::ImageMd5(v) => T::ImageMd5(v),
+                Property::LPageNo(v) => T::LPageNo(v),
+                Property::Poly(p) => T::Poly(p.points),
+                Property::PPageNo(n) => T::PPageNo(n),
+                Property::Order(n) => T::Order(n),
+                Property::ScanRes(x, y) => T::ScanRes(ScanResRepr { x, y }),
+                Property::TextAngle(v) => T::Textangle(v),
+                Property::XBBoxes(v) => T::XBBoxes(v),
+                Property::XConfs(v) => T::XConfs(v),
+                Property::XFont(v) => T::XFont(v),
+                Property::XFsize(v) => T::XFsize(v),
+                Property::XWconf(v) => T::XWconf(v),
+                Property::Other { name, values } => return PropertyRepr::Other { name, values },
+            })
+        }
+    }
+
+    impl From<PropertyRepr> for Property {
+        fn from(p: PropertyRepr) -> Self {
+            use TypedProperty as T;
+
+            match p {
+                PropertyRepr::Typed(t) => match t {
+                    T::BBox(b) => Property::BBox(b),
+                    T::Baseline(BaselineRepr { slope, offset }) => {
+                        Property::Baseline(Baseline::new(slope, offset))
+                    }
+                    T::Cflow(v) => Property::Cflow(v),
+                    T::Image(v) => Property::Image(v),
+                    T::ImageMd5(v) => Property::ImageMd5(v),
+                    T::LPageNo(v) => Property::LPageNo(v),
+                    T::Poly(points) => Property::Poly(Polygon::new(points)),
+                    T::PPageNo(n) => Property::PPageNo(n),
+                    T::Order(n) => Property::Order(n),
+                    T::ScanRes(ScanResRepr { x, y }) => Property::ScanRes(x, y),
+                    T::Textangle(v) => Property::TextAngle(v),
+                    T::XBBoxes(v) => Property::XBBoxes(v),
+                    T::XConfs(v) => Property::XConfs(v),
+                    T::XFont(v) => Property::XFont(v),
+                    T::XFsize(v) => Property::XFsize(v),
+                    T::XWconf(v) => Property::XWconf(v),
+                },
+                PropertyRepr::Other { name, values } => Property::Other { name, values },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_typed_properties() {
+        assert_eq!(
+            Property::parse("bbox", &["1", "2", "3", "4"]),
+            Property::BBox(BBox::new(1, 2, 3, 4))
+        );
+        assert_eq!(
+            Property::parse("scan_res", &["144", "144"]),
+            Property::ScanRes(144.0, 144.0)
+        );
+        assert_eq!(
+            Property::parse("baseline", &["0.015", "-3"]),
+            Property::Baseline(Baseline::new(0.015, -3.0))
+        );
+    }
+
+    #[test]
+    fn inexact_values_are_kept_verbatim() {
+        let p = Property::parse("x_wconf", &["95.0"]);
+        assert_eq!(
+            p,
+            Property::Other {
+                name: "x_wconf".to_string(),
+                values: vec!["95.0".to_string()]
+            }
+        );
+        assert_eq!(p.values(), vec!["95.0"]);
+
+        let p = Property::parse("bbox", &["1", "2", "3"]);
+        assert_eq!(p.name(), "bbox");
+        assert_eq!(p.values(), vec!["1", "2", "3"]);
+    }
+
+    #[test]
+    fn raw_round_trip() {
+        let values = ["12", "4", "30", "18", "30", "4", "44", "18"];
+        let p = Property::parse("x_bboxes", &values);
+        assert!(matches!(&p, Property::XBBoxes(b) if b.len() == 2));
+        assert_eq!(
+            p.to_raw(),
+            ("x_bboxes".to_string(), values.map(String::from).to_vec())
+        );
+    }
+}
@@ -0,0 +1,176 @@
+use crate::{
+    element::Element,
+    geometry::BBox,
+    hocr::HOCR,
+    property::Property,
+    spec_definitions::{elements::OCR_PAGE, properties},
+};
+
+/// Options for [`HOCR::recompute_bboxes_with_options`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecomputeOptions {
+    /// Add a `bbox` to elements without one if their children have boxes. Default: false.
+    pub fill_missing: bool,
+    /// Swap inverted coordinates, e.g. `x0 > x1`. Default: true.
+    pub normalize: bool,
+}
+
+impl Default for RecomputeOptions {
+    fn default() -> Self {
+        Self {
+            fill_missing: false,
+            normalize: true,
+        }
+    }
+}
+
+/// How the `bbox` of an element was changed by [`HOCR::recompute_bboxes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BBoxChangeKind {
+    /// Inverted coordinates were swapped.
+    Normalized,
+    /// The box was replaced by the union of the children's boxes.
+    Recomputed,
+    /// A missing box was added from the children's boxes.
+    Filled,
+}
+
+/// A `bbox` changed by [`HOCR::recompute_bboxes`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BBoxChange {
+    pub element_type: String,
+    pub id: Option<String>,
+    pub kind: BBoxChangeKind,
+    pub old: Option<BBox>,
+    pub new: BBox,
+}
+
+/// The union of the boxes of the children of an element, if any of them has one.
+pub(crate) fn children_bbox(e: &Element) -> Option<BBox> {
+    e.children
+        .iter()
+        .filter_map(Element::bbox)
+        .reduce(|a, b| a.union(&b))
+}
+
+fn recompute(e: &mut Element, options: RecomputeOptions, changes: &mut Vec<BBoxChange>) {
+    for child in &mut e.children {
+        recompute(child, options, changes);
+    }
+
+    let old = e.bbox();
+    let mut new = old;
+    let mut kind = None;
+
+    if let Some(bbox) = new.filter(|b| options.normalize && *b != b.normalized()) {
+        new = Some(bbox.normalized());
+        kind = Some(BBoxChangeKind::Normalized);
+    }
+
+    // the box of a page is the size of its image, not of its contents
+    if let Some(union) = children_bbox(e).filter(|_| e.element_type != OCR_PAGE) {
+        if new.is_some_and(|b| b != union) {
+            new = Some(union);
+            kind = Some(BBoxChangeKind::Recomputed);
+        } else if new.is_none() && options.fill_missing {
+            new = Some(union);
+            kind = Some(BBoxChangeKind::Filled);
+        }
+    }
+
+    if let (Some(kind), Some(bbox)) = (kind, new) {
+        e.set_property(properties::BBOX, Property::BBox(bbox).values());
+        changes.push(BBoxChange {
+            element_type: e.element_type.clone(),
+            id: e.id.clone(),
+            kind,
+            old,
+            new: bbox,
+        });
+    }
+}
+
+impl HOCR {
+    /// Recompute the `bbox` of every container as the union of its children's boxes, bottom-up,
+    /// and normalize inverted coordinates. See [`HOCR::recompute_bboxes_with_options`].
+    pub fn recompute_bboxes(&mut self) -> Vec<BBoxChange> {
+        self.recompute_bboxes_with_options(RecomputeOptions::default())
+    }
+
+    /// Recompute the `bbox` of every container as the union of its children's boxes, bottom-up.
+    ///
+    /// Elements without children with boxes, such as words, keep their box, as do pages, whose
+    /// box is the size of the page image. Returns the changed boxes in bottom-up order.
+    pub fn recompute_bboxes_with_options(&mut self, options: RecomputeOptions) -> Vec<BBoxChange> {
+        let mut changes = vec![];
+        for e in &mut self.elements {
+            recompute(e, options, &mut changes);
+        }
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::document;
+
+    fn doc() -> String {
+        document(
+            "ocr_page ocr_carea ocr_line ocrx_word",
+            r#"<div class="ocr_page" id="page_1" title="bbox 0 0 500 500">
+                <div class="ocr_carea" id="block_1">
+                    <span class="ocr_line" id="line_1" title="bbox 0 0 10 10">
+                        <span class="ocrx_word" id="word_1" title="bbox 50 30 10 10">a</span>
+                        <span class="ocrx_word" id="word_2" title="bbox 60 10 100 30">b</span>
+                    </span>
+                </div>
+            </div>"#,
+        )
+    }
+
+    #[test]
+    fn recompute_and_normalize() {
+        let mut hocr = HOCR::from_str(&doc()).unwrap();
+        let changes = hocr.recompute_bboxes();
+
+        let found: Vec<_> = changes
+            .iter()
+            .map(|c| (c.id.as_deref().unwrap(), c.kind, c.new))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (
+                    "word_1",
+                    BBoxChangeKind::Normalized,
+                    BBox::new(10, 10, 50, 30)
+                ),
+                (
+                    "line_1",
+                    BBoxChangeKind::Recomputed,
+                    BBox::new(10, 10, 100, 30)
+                ),
+            ]
+        );
+        assert_eq!(changes[1].old, Some(BBox::new(0, 0, 10, 10)));
+        assert!(hocr.recompute_bboxes().is_empty());
+    }
+
+    #[test]
+    fn fill_missing_boxes() {
+        let mut hocr = HOCR::from_str(&doc()).unwrap();
+        let options = RecomputeOptions {
+            fill_missing: true,
+            ..Default::default()
+        };
+        let changes = hocr.recompute_bboxes_with_options(options);
+
+        assert_eq!(changes.len(), 3);
+        assert_eq!(changes[2].id.as_deref(), Some("block_1"));
+        assert_eq!(changes[2].kind, BBoxChangeKind::Filled);
+        assert_eq!(hocr.elements[0].bbox(), Some(BBox::new(0, 0, 500, 500)));
+    }
+}
@@ -0,0 +1,212 @@
+use crate::{
+    geometry::BBox,
+    spec_definitions::elements::{OCRX_BLOCK, OCRX_WORD, OCR_CAREA, OCR_PAGE},
+    traits::{HocrDocument, HocrElement},
+};
+
+/// Number of bins in [`ConfidenceStats::histogram`]; each bin covers 10 confidence points.
+pub const HISTOGRAM_BINS: usize = 10;
+
+/// Summary statistics over the `x_wconf` word confidences of a document, page or block.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConfidenceStats {
+    /// Number of words with a confidence.
+    pub count: usize,
+    pub mean: Option<f64>,
+    pub median: Option<f64>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    /// Word counts in bins of 10 confidence points, from `[0, 10)` to `[90, 100]`.
+    pub histogram: [usize; HISTOGRAM_BINS],
+    /// Number of words with a confidence strictly below the threshold.
+    pub below_threshold: usize,
+}
+
+impl ConfidenceStats {
+    /// Compute statistics over the given confidences, counting those strictly below `threshold`.
+    pub fn from_confidences(confidences: impl IntoIterator<Item = f64>, threshold: f64) -> Self {
+        let mut values: Vec<f64> = confidences.into_iter().filter(|c| !c.is_nan()).collect();
+        values.sort_by(f64::total_cmp);
+
+        let count = values.len();
+        if count == 0 {
+            return Self::default();
+        }
+
+        let mut histogram = [0; HISTOGRAM_BINS];
+        for c in &values {
+            let bin = (c.clamp(0.0, 100.0) / 10.0) as usize;
+            histogram[bin.min(HISTOGRAM_BINS - 1)] += 1;
+        }
+
+        let median = if count % 2 == 0 {
+            (values[count / 2 - 1] + values[count / 2]) / 2.0
+        } else {
+            values[count / 2]
+        };
+
+        Self {
+            count,
+            mean: Some(values.iter().sum::<f64>() / count as f64),
+            median: Some(median),
+            min: values.first().copied(),
+            max: values.last().copied(),
+            histogram,
+            below_threshold: values.iter().take_while(|c| **c < threshold).count(),
+        }
+    }
+}
+
+/// Confidence statistics of a single page or block, identified by its `id` attribute.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ElementConfidenceStats {
+    pub id: Option<String>,
+    pub bbox: Option<BBox>,
+    pub stats: ConfidenceStats,
+}
+
+/// A word whose confidence is below the requested threshold.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LowConfidenceWord {
+    pub id: Option<String>,
+    pub text: String,
+    pub confidence: f64,
+    pub bbox: Option<BBox>,
+}
+
+fn self_and_descendants<E: HocrElement>(e: &E) -> impl Iterator<Item = &E> {
+    std::iter::once(e).chain(e.descendants())
+}
+
+fn words<E: HocrElement>(e: &E) -> impl Iterator<Item = &E> {
+    self_and_descendants(e).filter(|e| e.element_type() == OCRX_WORD)
+}
+
+/// Returns the `x_wconf` confidences of all words in this element, including the element itself.
+pub fn word_confidences<E: HocrElement>(e: &E) -> impl Iterator<Item = f64> + '_ {
+    words(e).filter_map(HocrElement::confidence)
+}
+
+/// Compute confidence statistics over all words in this element.
+pub fn element_stats<E: HocrElement>(e: &E, threshold: f64) -> ConfidenceStats {
+    ConfidenceStats::from_confidences(word_confidences(e), threshold)
+}
+
+/// Compute confidence statistics over all words in the document.
+pub fn document_stats<D: HocrDocument>(hocr: &D, threshold: f64) -> ConfidenceStats {
+    let confidences = hocr.elements().iter().flat_map(word_confidences);
+    ConfidenceStats::from_confidences(confidences, threshold)
+}
+
+fn stats_by_type<D: HocrDocument>(
+    hocr: &D,
+    types: &[&str],
+    threshold: f64,
+) -> Vec<ElementConfidenceStats> {
+    hocr.iter_document_order()
+        .filter(|e| types.contains(&e.element_type()))
+        .map(|e| ElementConfidenceStats {
+            id: e.id().map(str::to_string),
+            bbox: e.bbox(),
+            stats: element_stats(e, threshold),
+        })
+        .collect()
+}
+
+/// Compute confidence statistics for each `ocr_page` in the document, in document order.
+pub fn page_stats<D: HocrDocument>(hocr: &D, threshold: f64) -> Vec<ElementConfidenceStats> {
+    stats_by_type(hocr, &[OCR_PAGE], threshold)
+}
+
+/// Compute confidence statistics for each `ocr_carea` and `ocrx_block` in the document, in document order.
+pub fn block_stats<D: HocrDocument>(hocr: &D, threshold: f64) -> Vec<ElementConfidenceStats> {
+    stats_by_type(hocr, &[OCR_CAREA, OCRX_BLOCK], threshold)
+}
+
+/// Returns all words with a confidence strictly below `threshold`, in document order.
+pub fn low_confidence_words<D: HocrDocument>(hocr: &D, threshold: f64) -> Vec<LowConfidenceWord> {
+    hocr.iter_document_order()
+        .filter(|e| e.element_type() == OCRX_WORD)
+        .filter_map(|e| {
+            let confidence = e.confidence()?;
+            (confidence < threshold).then(|| LowConfidenceWord {
+                id: e.id().map(str::to_string),
+                text: e.text_content(),
+                confidence,
+                bbox: e.bbox(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::document;
+    use crate::HOCR;
+
+    fn doc() -> String {
+        document(
+            "ocr_page ocr_carea ocrx_word",
+            r#"<div class="ocr_page" id="page_1" title="bbox 0 0 100 100">
+                <div class="ocr_carea" id="block_1_1" title="bbox 0 0 100 50">
+                    <span class="ocrx_word" id="word_1_1" title="bbox 0 0 10 10; x_wconf 95">good</span>
+                    <span class="ocrx_word" id="word_1_2" title="bbox 10 0 20 10; x_wconf 40">bad</span>
+                </div>
+                <div class="ocr_carea" id="block_1_2" title="bbox 0 50 100 100">
+                    <span class="ocrx_word" id="word_1_3" title="bbox 0 50 10 60; x_wconf 100">fine</span>
+                    <span class="ocrx_word" id="word_1_4" title="bbox 10 50 20 60">none</span>
+                </div>
+            </div>"#,
+        )
+    }
+
+    #[test]
+    fn confidence_stats() {
+        let stats = ConfidenceStats::from_confidences([95.0, 40.0, 100.0, 61.0], 60.0);
+        assert_eq!(stats.count, 4);
+        assert_eq!(stats.mean, Some(74.0));
+        assert_eq!(stats.median, Some(78.0));
+        assert_eq!(stats.min, Some(40.0));
+        assert_eq!(stats.max, Some(100.0));
+        assert_eq!(stats.histogram[4], 1);
+        assert_eq!(stats.histogram[9], 2);
+        assert_eq!(stats.below_threshold, 1);
+    }
+
+    #[test]
+    fn confidence_stats_empty() {
+        let stats = ConfidenceStats::from_confidences([], 60.0);
+        assert_eq!(stats.count, 0);
+        assert_eq!(stats.mean, None);
+    }
+
+    #[test]
+    fn document_page_and_block_stats() {
+        let hocr = HOCR::from_str(&doc()).unwrap();
+        assert_eq!(document_stats(&hocr, 50.0).count, 3);
+
+        let pages = page_stats(&hocr, 50.0);
+        assert_eq!(pages.len(), 1);
+        assert_eq!(pages[0].id.as_deref(), Some("page_1"));
+        assert_eq!(pages[0].stats.below_threshold, 1);
+
+        let blocks = block_stats(&hocr, 50.0);
+        let ids: Vec<_> = blocks.iter().map(|b| b.id.as_deref().unwrap()).collect();
+        assert_eq!(ids, vec!["block_1_1", "block_1_2"]);
+        assert_eq!(blocks[1].stats.mean, Some(100.0));
+    }
+
+    #[test]
+    fn low_confidence_report() {
+        let hocr = HOCR::from_str(&doc()).unwrap();
+        let words = low_confidence_words(&hocr, 96.0);
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].id.as_deref(), Some("word_1_1"));
+        assert_eq!(words[1].text, "bad");
+        assert_eq!(words[1].bbox, Some(BBox::new(10, 0, 20, 10)));
+    }
+}
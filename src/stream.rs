@@ -0,0 +1,427 @@
+//! [`HOCRReader`](crate::stream::HOCRReader) reads from any [`BufRead`](std::io::BufRead) and
+//! yields [`Event`](crate::stream::Event)s as the document is read, applying the same element and
+//! property checks as [`HOCR::from_str`](crate::HOCR::from_str).
+//! [`HOCRReader::pages`](crate::stream::HOCRReader::pages) builds each `ocr_page` subtree as an
+//! [`Element`] and drops it once it has been returned, so memory use is bounded by the largest
+//! page.
+//!
+//! ```no_run
+//! use std::{fs::File, io::BufReader};
+//! use hocr_parser::stream::HOCRReader;
+//!
+//! let file = BufReader::new(File::open("book.hocr").unwrap());
+//! for page in HOCRReader::new(file).pages() {
+//!     let page = page.unwrap();
+//!     println!("{:?}: {} words", page.id, page.plain_text().split_whitespace().count());
+//! }
+//! ```
+
+use std::{collections::VecDeque, io::BufRead};
+
+use quick_xml::events::{BytesStart, Event as XmlEvent};
+
+use crate::{
+    element::Element,
+    error::{ErrorLocation, Result},
+    hocr::DocumentMetadata,
+    parsing::{check_element, check_property_name, parse_properties},
+    spec_definitions::elements::{OCRX_WORD, OCR_PAGE},
+    HOCRParserError,
+};
+
+/// An event produced by [`HOCRReader`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    /// The document metadata, emitted once the `<head>` element has been read.
+    Metadata(DocumentMetadata),
+    /// Start of an hOCR element.
+    StartElement {
+        /// The hOCR element class, e.g. `ocr_line`.
+        kind: String,
+        id: Option<String>,
+        /// The XHTML tag name, e.g. `span`.
+        tag: String,
+        lang: Option<String>,
+        /// Attributes other than `class`, `id`, `title` and `lang`.
+        attributes: Vec<(String, String)>,
+        /// Properties from the `title` attribute, in order.
+        properties: Vec<(String, Vec<String>)>,
+    },
+    /// Text content of the current element, either before its first child or after the end of
+    /// a child, which [`HOCRReader::pages`] stores as that child's tail. Whitespace-only text is
+    /// skipped.
+    Text(String),
+    /// End of the hOCR element started by the matching [`Event::StartElement`].
+    EndElement { kind: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Prolog,
+    Head,
+    Body,
+    Epilog,
+    Done,
+}
+
+/// Pull parser yielding [`Event`]s from hOCR XML.
+///
+/// Parsing stops after the first error.
+pub struct HOCRReader<R: BufRead> {
+    reader: quick_xml::Reader<R>,
+    buf: Vec<u8>,
+    state: State,
+    meta_tags: Vec<(String, String)>,
+    metadata: Option<DocumentMetadata>,
+    open: Vec<String>,
+    pending: VecDeque<Event>,
+}
+
+fn xml_error(position: u64, e: impl std::fmt::Display) -> HOCRParserError {
+    HOCRParserError::XMLStreamError(position, e.to_string())
+}
+
+impl<R: BufRead> HOCRReader<R> {
+    /// Create a new [`HOCRReader`] reading hOCR XML from `reader`.
+    pub fn new(reader: R) -> Self {
+        let mut reader = quick_xml::Reader::from_reader(reader);
+        reader.config_mut().trim_text(false);
+
+        Self {
+            reader,
+            buf: vec![],
+            state: State::Prolog,
+            meta_tags: vec![],
+            metadata: None,
+            open: vec![],
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// The document metadata, available once [`Event::Metadata`] has been yielded.
+    pub fn metadata(&self) -> Option<&DocumentMetadata> {
+        self.metadata.as_ref()
+    }
+
+    /// Consume the reader and yield each `ocr_page` element in turn.
+    pub fn pages(self) -> Pages<R> {
+        Pages { reader: self }
+    }
+
+    fn start_element(e: &BytesStart, position: u64, in_word: bool) -> Result<Event> {
+        let mut class = None;
+        let mut id = None;
+        let mut title = None;
+        let mut lang = None;
+        let mut attributes = vec![];
+
+        for a in e.attributes() {
+            let a = a.map_err(|e| xml_error(position, e))?;
+            let key = a.key;
+            if key.prefix().is_some() || key.as_ref() == b"xmlns" {
+                continue;
+            }
+
+            let name = String::from_utf8_lossy(key.local_name().as_ref()).into_owned();
+            let value = a
+                .unescape_value()
+                .map_err(|e| xml_error(position, e))?
+                .into_owned();
+            match name.as_str() {
+                "class" => class = Some(value),
+                "id" => id = Some(value),
+                "title" => title = Some(value),
+                "lang" => lang = Some(value),
+                _ => attributes.push((name, value)),
+            }
+        }
+
+        let kind = class.unwrap_or_default();
+        let tag = String::from_utf8_lossy(e.local_name().as_ref()).into_owned();
+        if !check_element(&kind, &tag, in_word) {
+            return Err(HOCRParserError::UnknownElement(ErrorLocation::Byte(
+                position,
+            )));
+        }
+
+        let properties = parse_properties(title.as_deref().unwrap_or(""));
+        if properties
+            .iter()
+            .any(|(name, _)| !check_property_name(name))
+        {
+            return Err(HOCRParserError::UnknownProperty(ErrorLocation::Byte(
+                position,
+            )));
+        }
+
+        Ok(Event::StartElement {
+            kind,
+            id,
+            tag,
+            lang,
+            attributes,
+            properties: properties
+                .into_iter()
+                .map(|(name, values)| {
+                    (
+                        name.to_string(),
+                        values.into_iter().map(str::to_string).collect(),
+                    )
+                })
+                .collect(),
+        })
+    }
+
+    fn read_next(&mut self) -> Result<()> {
+        let position = self.reader.buffer_position();
+        self.buf.clear();
+        let event = self
+            .reader
+            .read_event_into(&mut self.buf)
+            .map_err(|e| xml_error(self.reader.error_position(), e))?;
+
+        let is_empty = matches!(event, XmlEvent::Empty(_));
+        match event {
+            XmlEvent::Start(e) | XmlEvent::Empty(e) => {
+                match (self.state, e.local_name().as_ref()) {
+                    (State::Prolog, b"head") => self.state = State::Head,
+                    (State::Prolog, b"body") => return Err(HOCRParserError::NoHeadElement),
+                    (State::Head, b"meta") => {
+                        let attribute = |name: &str| {
+                            e.try_get_attribute(name)
+                                .ok()
+                                .flatten()
+                                .and_then(|a| a.unescape_value().ok())
+                                .map(|v| v.into_owned())
+                        };
+                        if let (Some(name), Some(content)) =
+                            (attribute("name"), attribute("content"))
+                        {
+                            self.meta_tags.push((name, content));
+                        }
+                    }
+                    (State::Epilog, b"body") if is_empty => self.state = State::Done,
+                    (State::Epilog, b"body") => self.state = State::Body,
+                    (State::Body, _) => {
+                        // formatting elements are the only accepted elements without a class
+                        let in_word = self
+                            .open
+                            .last()
+                            .is_some_and(|kind| kind == OCRX_WORD || kind.is_empty());
+                        let start = Self::start_element(&e, position, in_word)?;
+                        let Event::StartElement { kind, .. } = &start else {
+                            unreachable!()
+                        };
+                        let kind = kind.clone();
+                        self.pending.push_back(start);
+                        if is_empty {
+                            self.pending.push_back(Event::EndElement { kind });
+                        } else {
+                            self.open.push(kind);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            XmlEvent::End(e) => match (self.state, e.local_name().as_ref()) {
+                (State::Head, b"head") => {
+                    let metadata = DocumentMetadata::from_meta_tags(&self.meta_tags)?;
+                    self.meta_tags.clear();
+                    self.metadata = Some(metadata.clone());
+                    self.pending.push_back(Event::Metadata(metadata));
+                    // the body is only accepted after the head
+                    self.state = State::Epilog;
+                }
+                (State::Body, _) => match self.open.pop() {
+                    Some(kind) => self.pending.push_back(Event::EndElement { kind }),
+                    None => self.state = State::Done,
+                },
+                _ => {}
+            },
+            XmlEvent::Text(e) if self.state == State::Body && !self.open.is_empty() => {
+                let text = e.unescape().map_err(|e| xml_error(position, e))?;
+                if !text.chars().all(char::is_whitespace) {
+                    self.pending.push_back(Event::Text(text.into_owned()));
+                }
+            }
+            XmlEvent::CData(e) if self.state == State::Body && !self.open.is_empty() => {
+                let text = e.decode().map_err(|e| xml_error(position, e))?;
+                if !text.chars().all(char::is_whitespace) {
+                    self.pending.push_back(Event::Text(text.into_owned()));
+                }
+            }
+            XmlEvent::Eof => {
+                self.state = match self.state {
+                    State::Prolog | State::Head => return Err(HOCRParserError::NoHeadElement),
+                    State::Epilog => return Err(HOCRParserError::NoBodyElement),
+                    _ => State::Done,
+                };
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Iterator for HOCRReader<R> {
+    type Item = Result<Event>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(Ok(event));
+            }
+            if self.state == State::Done {
+                return None;
+            }
+            if let Err(e) = self.read_next() {
+                self.state = State::Done;
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+/// Iterator over the `ocr_page` elements of a document, created by [`HOCRReader::pages`].
+pub struct Pages<R: BufRead> {
+    reader: HOCRReader<R>,
+}
+
+impl<R: BufRead> Pages<R> {
+    /// The document metadata, available once the first page has been read.
+    pub fn metadata(&self) -> Option<&DocumentMetadata> {
+        self.reader.metadata()
+    }
+}
+
+impl<R: BufRead> Iterator for Pages<R> {
+    type Item = Result<Element>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut stack: Vec<Element> = vec![];
+
+        for event in self.reader.by_ref() {
+            match event {
+                Err(e) => return Some(Err(e)),
+                Ok(Event::StartElement {
+                    kind,
+                    id,
+                    tag,
+                    lang,
+                    attributes,
+                    properties,
+                }) if kind == OCR_PAGE || !stack.is_empty() => stack.push(Element {
+                    element_type: kind,
+                    id,
+                    tag_name: tag,
+                    properties,
+                    lang,
+                    attributes,
+                    text: None,
+                    tail: None,
+                    children: vec![],
+                }),
+                // text after a child element is the tail of that child
+                Ok(Event::Text(text)) => {
+                    if let Some(e) = stack.last_mut() {
+                        let target = match e.children.last_mut() {
+                            Some(child) => &mut child.tail,
+                            None => &mut e.text,
+                        };
+                        target.get_or_insert_with(String::new).push_str(&text);
+                    }
+                }
+                Ok(Event::EndElement { .. }) if !stack.is_empty() => {
+                    let e = stack.pop().unwrap();
+                    match stack.last_mut() {
+                        Some(parent) => parent.children.push(e),
+                        None => return Some(Ok(e)),
+                    }
+                }
+                Ok(_) => {}
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::document;
+    use crate::HOCR;
+
+    fn doc() -> String {
+        document(
+            "ocr_page ocrx_word",
+            r#"<div class="ocr_page" id="page_1" title="bbox 0 0 100 100">
+                <span class="ocrx_word" id="w1" dir="rtl" title="bbox 0 0 10 10">a &amp; b</span>
+                <span class="ocrx_word" id="w2"/>
+            </div>"#,
+        )
+    }
+
+    #[test]
+    fn stream_events() {
+        let events: Vec<_> = HOCRReader::new(doc().as_bytes())
+            .collect::<Result<_>>()
+            .unwrap();
+
+        assert!(matches!(&events[0], Event::Metadata(m) if m.system == "test"));
+        assert!(matches!(
+            &events[2],
+            Event::StartElement { kind, attributes, properties, .. }
+                if kind == "ocrx_word" && attributes[0].0 == "dir" && properties[0].0 == "bbox"
+        ));
+        assert_eq!(events[3], Event::Text("a & b".to_string()));
+        assert_eq!(events.len(), 8);
+        assert_eq!(
+            events[7],
+            Event::EndElement {
+                kind: OCR_PAGE.to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn stream_pages_match_dom() {
+        let xml = std::fs::read_to_string("examples/data/paper-image.hocr").unwrap();
+        let hocr = HOCR::from_str(&xml).unwrap();
+
+        let mut pages = HOCRReader::new(xml.as_bytes()).pages();
+        let page = pages.next().unwrap().unwrap();
+        assert_eq!(page, hocr.elements[0]);
+        assert!(pages.next().is_none());
+        assert_eq!(pages.metadata().unwrap(), &hocr.document_metadata());
+    }
+
+    #[test]
+    fn stream_keeps_tail_text() {
+        let xml = doc().replace("a &amp; b", "Hel<em>lo</em>!");
+        let page = HOCRReader::new(xml.as_bytes())
+            .pages()
+            .next()
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(page, HOCR::from_str(&xml).unwrap().elements[0]);
+        assert_eq!(page.children[0].children[0].tail.as_deref(), Some("!"));
+    }
+
+    #[test]
+    fn stream_errors() {
+        let unknown = doc().replace("ocrx_word\" id=\"w2", "unknown\" id=\"w2");
+        let error = HOCRReader::new(unknown.as_bytes()).pages().next().unwrap();
+        assert!(matches!(
+            error,
+            Err(HOCRParserError::UnknownElement(ErrorLocation::Byte(_)))
+        ));
+
+        let no_body = "<html><head><meta name=\"ocr-system\" content=\"test\"/>\
+            <meta name=\"ocr-capabilities\" content=\"ocr_page\"/></head></html>";
+        let error = HOCRReader::new(no_body.as_bytes()).last().unwrap();
+        assert!(matches!(error, Err(HOCRParserError::NoBodyElement)));
+    }
+}
@@ -0,0 +1,236 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    hocr::HOCR,
+    parsing::is_formatting_element,
+    spec_definitions::{
+        elements::{
+            OCRX_LINE, OCRX_WORD, OCR_CAPTION, OCR_FOOTER, OCR_HEADER, OCR_LINE, OCR_PAGE,
+            OCR_TEXTFLOAT,
+        },
+        properties,
+    },
+    traits::{HocrDocument, HocrElement},
+    units::{scan_res, POINTS_PER_INCH},
+};
+
+/// Element types treated as text lines. Tesseract emits `ocr_caption`, `ocr_header`,
+/// `ocr_footer` and `ocr_textfloat` in place of `ocr_line` for lines in those regions.
+pub(crate) const LINE_ELEMENTS: [&str; 6] = [
+    OCR_LINE,
+    OCRX_LINE,
+    OCR_CAPTION,
+    OCR_HEADER,
+    OCR_FOOTER,
+    OCR_TEXTFLOAT,
+];
+
+/// A summary of the contents of an hOCR document, see [`HOCR::summary`].
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Summary {
+    /// Number of elements of each class.
+    pub element_counts: BTreeMap<String, usize>,
+    pub pages: usize,
+    pub lines: usize,
+    pub words: usize,
+    /// Average height of word bounding boxes in pixels.
+    pub average_word_height_px: Option<f64>,
+    /// Average height of word bounding boxes in points, over words on pages with a `scan_res`.
+    pub average_word_height_pt: Option<f64>,
+    /// Number of words in each language, taken from the (inherited) `lang` attributes.
+    pub languages: BTreeMap<String, usize>,
+    /// Number of words set in each font, taken from the (inherited) `x_font` and `x_fsize` properties.
+    pub fonts: Vec<FontUsage>,
+    pub page_dimensions: Vec<PageDimensions>,
+}
+
+/// Number of words set in a font and size.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FontUsage {
+    pub font: Option<String>,
+    pub size: Option<f64>,
+    pub words: usize,
+}
+
+/// Dimensions of an `ocr_page`, taken from its `bbox` and `scan_res` properties.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PageDimensions {
+    pub id: Option<String>,
+    pub width: i32,
+    pub height: i32,
+    /// Horizontal and vertical resolution in dots per inch.
+    pub scan_res: Option<(f64, f64)>,
+}
+
+#[derive(Clone, Default)]
+struct Context<'a> {
+    dpi: Option<f64>,
+    lang: Option<&'a str>,
+    font: Option<&'a str>,
+    size: Option<f64>,
+}
+
+#[derive(Default)]
+struct Accumulator {
+    summary: Summary,
+    height_px: (f64, usize),
+    height_pt: (f64, usize),
+}
+
+fn visit<'a, E: HocrElement>(e: &'a E, mut ctx: Context<'a>, acc: &mut Accumulator) {
+    let summary = &mut acc.summary;
+    // formatting elements such as `<strong>` are not hOCR elements
+    if !is_formatting_element(e.element_type(), e.tag_name()) {
+        *summary
+            .element_counts
+            .entry(e.element_type().to_string())
+            .or_default() += 1;
+    }
+
+    if let Some(lang) = e.lang() {
+        ctx.lang = Some(lang);
+    }
+    if let Some(font) = e.property(properties::X_FONT).and_then(|v| v.first()) {
+        ctx.font = Some(font.as_ref());
+    }
+    if let Some(size) = e
+        .property(properties::X_FSIZE)
+        .and_then(|v| v.first()?.as_ref().parse().ok())
+    {
+        ctx.size = Some(size);
+    }
+
+    match e.element_type() {
+        OCR_PAGE => {
+            summary.pages += 1;
+            let scan_res = scan_res(e);
+            ctx.dpi = scan_res.map(|(_, y)| y);
+            if let Some(bbox) = e.bbox() {
+                summary.page_dimensions.push(PageDimensions {
+                    id: e.id().map(str::to_string),
+                    width: bbox.width(),
+                    height: bbox.height(),
+                    scan_res,
+                });
+            }
+        }
+        OCRX_WORD => {
+            summary.words += 1;
+
+            if let Some(bbox) = e.bbox() {
+                let height = bbox.height() as f64;
+                acc.height_px.0 += height;
+                acc.height_px.1 += 1;
+                if let Some(dpi) = ctx.dpi.filter(|d| *d > 0.0) {
+                    acc.height_pt.0 += height / dpi * POINTS_PER_INCH;
+                    acc.height_pt.1 += 1;
+                }
+            }
+
+            if let Some(lang) = ctx.lang {
+                *summary.languages.entry(lang.to_string()).or_default() += 1;
+            }
+
+            if ctx.font.is_some() || ctx.size.is_some() {
+                let font = ctx.font.map(str::to_string);
+                match summary
+                    .fonts
+                    .iter_mut()
+                    .find(|f| f.font == font && f.size == ctx.size)
+                {
+                    Some(usage) => usage.words += 1,
+                    None => summary.fonts.push(FontUsage {
+                        font,
+                        size: ctx.size,
+                        words: 1,
+                    }),
+                }
+            }
+        }
+        t if LINE_ELEMENTS.contains(&t) => summary.lines += 1,
+        _ => {}
+    }
+
+    for child in e.children() {
+        visit(child, ctx.clone(), acc);
+    }
+}
+
+fn average((sum, count): (f64, usize)) -> Option<f64> {
+    (count > 0).then(|| sum / count as f64)
+}
+
+pub(crate) fn summarize<D: HocrDocument + ?Sized>(hocr: &D) -> Summary {
+    let mut acc = Accumulator::default();
+    let root = Context {
+        lang: match hocr.langs() {
+            Some([lang]) => Some(lang.as_ref()),
+            _ => None,
+        },
+        ..Context::default()
+    };
+
+    for e in hocr.elements() {
+        visit(e, root.clone(), &mut acc);
+    }
+
+    acc.summary.average_word_height_px = average(acc.height_px);
+    acc.summary.average_word_height_pt = average(acc.height_pt);
+    acc.summary
+}
+
+impl HOCR {
+    /// Returns a summary of the document: element counts, word and line counts, word heights,
+    /// languages, font usage and page dimensions.
+    pub fn summary(&self) -> Summary {
+        HocrDocument::summary(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::document;
+
+    #[test]
+    fn summary_of_document() {
+        let xml = document(
+            "ocr_page ocr_line ocrx_word",
+            r#"<div class="ocr_page" id="page_1" title="bbox 0 0 1200 1600; scan_res 144 144">
+                <span class="ocr_line" lang="deu" title="bbox 0 0 100 20; x_font &quot;Times&quot;; x_fsize 10">
+                    <span class="ocrx_word" title="bbox 0 0 40 20">Hallo</span>
+                    <span class="ocrx_word" lang="eng" title="bbox 50 0 100 40; x_fsize 12">World</span>
+                </span>
+            </div>"#,
+        );
+        let summary = HOCR::from_str(&xml).unwrap().summary();
+
+        assert_eq!(summary.pages, 1);
+        assert_eq!(summary.lines, 1);
+        assert_eq!(summary.words, 2);
+        assert_eq!(summary.element_counts["ocrx_word"], 2);
+        assert_eq!(summary.average_word_height_px, Some(30.0));
+        assert_eq!(summary.average_word_height_pt, Some(15.0));
+        assert_eq!(summary.languages["deu"], 1);
+        assert_eq!(summary.languages["eng"], 1);
+        assert_eq!(summary.fonts.len(), 2);
+        assert_eq!(summary.fonts[1].font.as_deref(), Some("Times"));
+        assert_eq!(summary.fonts[1].size, Some(12.0));
+        assert_eq!(summary.page_dimensions[0].width, 1200);
+        assert_eq!(summary.page_dimensions[0].scan_res, Some((144.0, 144.0)));
+    }
+
+    #[test]
+    fn summary_of_example() {
+        let xml = std::fs::read_to_string("examples/data/paper-image.hocr").unwrap();
+        let summary = HOCR::from_str(&xml).unwrap().summary();
+
+        assert_eq!(summary.pages, 1);
+        assert_eq!(summary.words, 510);
+        assert_eq!(summary.lines, 56 + 3 + 2);
+        assert_eq!(summary.languages["eng"], 510);
+    }
+}
@@ -0,0 +1,24 @@
+//! Fixtures shared by the unit tests.
+
+/// An hOCR document from the `test` system with the given capabilities and body markup.
+pub(crate) fn document(capabilities: &str, body: &str) -> String {
+    document_with_meta(capabilities, "", body)
+}
+
+/// Like [`document`], with additional `<meta>` tags in the head.
+pub(crate) fn document_with_meta(capabilities: &str, meta: &str, body: &str) -> String {
+    system_document("test", capabilities, meta, body)
+}
+
+/// Like [`document_with_meta`], from the given OCR system.
+pub(crate) fn system_document(system: &str, capabilities: &str, meta: &str, body: &str) -> String {
+    format!(
+        r#"<html><head>
+<meta name="ocr-system" content="{system}"/>
+<meta name="ocr-capabilities" content="{capabilities}"/>
+{meta}
+</head><body>
+{body}
+</body></html>"#
+    )
+}
@@ -0,0 +1,54 @@
+use crate::{
+    element::Element,
+    hocr::HOCR,
+    spec_definitions::elements::{
+        OCRX_WORD, OCR_CHEM, OCR_CINFO, OCR_DROPCAP, OCR_GLYPH, OCR_GLYPHS, OCR_MATH, OCR_PAGE,
+    },
+    summary::LINE_ELEMENTS,
+    traits::{HocrDocument, HocrElement},
+};
+
+fn separator<E: HocrElement>(children: &[E]) -> &'static str {
+    match children.first().map(HocrElement::element_type) {
+        Some(t) if LINE_ELEMENTS.contains(&t) => "\n",
+        Some(
+            OCRX_WORD | OCR_GLYPH | OCR_GLYPHS | OCR_DROPCAP | OCR_MATH | OCR_CHEM | OCR_CINFO,
+        ) => " ",
+        Some(OCR_PAGE) => "\n\x0c",
+        _ => "\n\n",
+    }
+}
+
+/// Plain text of the given sibling elements, joined by the separator for their type.
+pub(crate) fn joined_text<E: HocrElement>(elements: &[E]) -> String {
+    let parts: Vec<String> = elements
+        .iter()
+        .map(element_text)
+        .filter(|t| !t.is_empty())
+        .collect();
+
+    parts.join(separator(elements))
+}
+
+pub(crate) fn element_text<E: HocrElement>(e: &E) -> String {
+    if e.element_type() == OCRX_WORD || e.children().is_empty() {
+        return e.text_content().trim().to_string();
+    }
+
+    joined_text(e.children())
+}
+
+impl Element {
+    /// Returns the plain text of this element: words are separated by spaces,
+    /// lines by newlines and blocks by empty lines.
+    pub fn plain_text(&self) -> String {
+        HocrElement::plain_text(self)
+    }
+}
+
+impl HOCR {
+    /// Returns the plain text of the document, with pages separated by form feeds.
+    pub fn plain_text(&self) -> String {
+        HocrDocument::plain_text(self)
+    }
+}
@@ -0,0 +1,312 @@
+use crate::{
+    baseline::Baseline,
+    element::{Element, ElementBorrowed},
+    geometry::BBox,
+    hocr::{HOCRBorrowed, HOCR},
+    polygon::Polygon,
+    iter::{breadth_first, document_order, ElementsIterator},
+    spec_definitions::properties,
+    summary::{summarize, Summary},
+    text::{element_text, joined_text},
+    validate::{validate_document, ValidationIssue},
+};
+
+/// Common interface of the owned [`Element`] and the borrowed [`ElementBorrowed`].
+///
+/// Traversal, text extraction, statistics and validation are implemented once against this
+/// trait, so helpers written for it work with either representation.
+pub trait HocrElement: Sized {
+    /// String type of property values, [`String`] or `&str`.
+    type Value: AsRef<str>;
+
+    /// The hOCR element class, e.g. `ocr_line`.
+    fn element_type(&self) -> &str;
+
+    fn id(&self) -> Option<&str>;
+
+    /// Name of the XHTML tag, e.g. `div` or `span`.
+    fn tag_name(&self) -> &str;
+
+    fn lang(&self) -> Option<&str>;
+
+    /// The text directly contained in the element, before its first child element.
+    fn text(&self) -> Option<&str>;
+
+    /// The text following the element, before its next sibling element.
+    fn tail(&self) -> Option<&str>;
+
+    fn children(&self) -> &[Self];
+
+    /// Returns an iterator over the property names and their values.
+    fn properties(&self) -> impl Iterator<Item = (&str, &[Self::Value])>;
+
+    /// Returns the values of the property with the given name, if present.
+    fn property(&self, name: &str) -> Option<&[Self::Value]> {
+        self.properties()
+            .find(|(n, _)| *n == name)
+            .map(|(_, values)| values)
+    }
+
+    /// Returns the parsed `bbox` property, if present and well-formed.
+    fn bbox(&self) -> Option<BBox> {
+        self.property(properties::BBOX).and_then(BBox::from_values)
+    }
+
+    /// Returns the parsed `baseline` property, if present and well-formed.
+    fn baseline(&self) -> Option<Baseline> {
+        self.property(properties::BASELINE)
+            .and_then(Baseline::from_values)
+    }
+
+    /// Returns the parsed `poly` property, if present and well-formed.
+    fn poly(&self) -> Option<Polygon> {
+        self.property(properties::POLY).and_then(Polygon::from_values)
+    }
+
+    /// Returns the `poly` of this element, or its `bbox` as a rectangle.
+    fn shape(&self) -> Option<Polygon> {
+        self.poly()
+            .or_else(|| self.bbox().map(|b| Polygon::from_bbox(&b)))
+    }
+
+    /// Returns the parsed `x_wconf` property, if present and well-formed.
+    fn confidence(&self) -> Option<f64> {
+        self.property(properties::X_WCONF)?
+            .first()?
+            .as_ref()
+            .parse()
+            .ok()
+    }
+
+    /// Returns the text of this element and all of its descendants, concatenated.
+    fn text_content(&self) -> String {
+        let mut text = self.text().unwrap_or("").to_string();
+        for child in self.children() {
+            text.push_str(&child.text_content());
+            text.push_str(child.tail().unwrap_or(""));
+        }
+        text
+    }
+
+    /// Returns the plain text of this element: words are separated by spaces,
+    /// lines by newlines and blocks by empty lines.
+    fn plain_text(&self) -> String {
+        element_text(self)
+    }
+
+    /// Returns an iterator over all descendants of this hOCR element.
+    /// Note that the iterator returns elements in a breadth-first order.
+    fn descendants(&self) -> ElementsIterator<'_, Self> {
+        breadth_first(self.children())
+    }
+}
+
+/// Common interface of the owned [`HOCR`] and the borrowed [`HOCRBorrowed`].
+pub trait HocrDocument {
+    type Element: HocrElement;
+
+    /// Content of the `ocr-system` metadata.
+    fn system(&self) -> &str;
+
+    /// Content of the `ocr-capabilities` metadata.
+    fn capabilities(&self) -> &[<Self::Element as HocrElement>::Value];
+
+    /// Content of the `ocr-number-of-pages` metadata, if present.
+    fn number_of_pages(&self) -> Option<u32>;
+
+    /// Content of the `ocr-langs` metadata, if present.
+    fn langs(&self) -> Option<&[<Self::Element as HocrElement>::Value]>;
+
+    /// The top-level elements of the body.
+    fn elements(&self) -> &[Self::Element];
+
+    /// Returns an iterator over all elements in the hOCR document.
+    /// Note that the iterator returns elements in a breadth-first order.
+    fn iter(&self) -> ElementsIterator<'_, Self::Element> {
+        breadth_first(self.elements())
+    }
+
+    /// Returns an iterator over all elements in the hOCR document in document order,
+    /// i.e. a depth-first pre-order traversal.
+    fn iter_document_order(&self) -> ElementsIterator<'_, Self::Element> {
+        document_order(self.elements())
+    }
+
+    /// Returns the plain text of the document, with pages separated by form feeds.
+    fn plain_text(&self) -> String {
+        joined_text(self.elements())
+    }
+
+    /// Check property values and document metadata beyond what parsing enforces.
+    ///
+    /// Returns an empty vector if no problems were found.
+    fn validate(&self) -> Vec<ValidationIssue> {
+        validate_document(self)
+    }
+
+    /// Returns a summary of the document: element counts, word and line counts, word heights,
+    /// languages, font usage and page dimensions.
+    fn summary(&self) -> Summary {
+        summarize(self)
+    }
+}
+
+impl HocrElement for Element {
+    type Value = String;
+
+    fn element_type(&self) -> &str {
+        &self.element_type
+    }
+
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn tag_name(&self) -> &str {
+        &self.tag_name
+    }
+
+    fn lang(&self) -> Option<&str> {
+        self.lang.as_deref()
+    }
+
+    fn text(&self) -> Option<&str> {
+        self.text.as_deref()
+    }
+
+    fn tail(&self) -> Option<&str> {
+        self.tail.as_deref()
+    }
+
+    fn children(&self) -> &[Self] {
+        &self.children
+    }
+
+    fn properties(&self) -> impl Iterator<Item = (&str, &[String])> {
+        self.properties
+            .iter()
+            .map(|(name, values)| (name.as_str(), values.as_slice()))
+    }
+}
+
+impl<'a> HocrElement for ElementBorrowed<'a> {
+    type Value = &'a str;
+
+    fn element_type(&self) -> &str {
+        self.element_type
+    }
+
+    fn id(&self) -> Option<&str> {
+        self.id
+    }
+
+    fn tag_name(&self) -> &str {
+        self.tag_name
+    }
+
+    fn lang(&self) -> Option<&str> {
+        self.lang
+    }
+
+    fn text(&self) -> Option<&str> {
+        self.text
+    }
+
+    fn tail(&self) -> Option<&str> {
+        self.tail
+    }
+
+    fn children(&self) -> &[Self] {
+        &self.children
+    }
+
+    fn properties(&self) -> impl Iterator<Item = (&str, &[&'a str])> {
+        self.properties
+            .iter()
+            .map(|(name, values)| (*name, values.as_slice()))
+    }
+}
+
+impl HocrDocument for HOCR {
+    type Element = Element;
+
+    fn system(&self) -> &str {
+        &self.system
+    }
+
+    fn capabilities(&self) -> &[String] {
+        &self.capabilities
+    }
+
+    fn number_of_pages(&self) -> Option<u32> {
+        self.number_of_pages
+    }
+
+    fn langs(&self) -> Option<&[String]> {
+        self.langs.as_deref()
+    }
+
+    fn elements(&self) -> &[Element] {
+        &self.elements
+    }
+}
+
+impl<'input> HocrDocument for HOCRBorrowed<'input> {
+    type Element = ElementBorrowed<'input>;
+
+    fn system(&self) -> &str {
+        self.system
+    }
+
+    fn capabilities(&self) -> &[&'input str] {
+        &self.capabilities
+    }
+
+    fn number_of_pages(&self) -> Option<u32> {
+        self.number_of_pages
+    }
+
+    fn langs(&self) -> Option<&[&'input str]> {
+        self.langs.as_deref()
+    }
+
+    fn elements(&self) -> &[ElementBorrowed<'input>] {
+        &self.elements
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word_count<D: HocrDocument>(hocr: &D) -> usize {
+        hocr.iter()
+            .filter(|e| e.element_type() == "ocrx_word")
+            .count()
+    }
+
+    #[test]
+    fn owned_and_borrowed_agree() {
+        let xml = std::fs::read_to_string("examples/data/paper-image.hocr").unwrap();
+        let options = roxmltree::ParsingOptions {
+            allow_dtd: true,
+            ..Default::default()
+        };
+        let doc = roxmltree::Document::parse_with_options(&xml, options).unwrap();
+        let borrowed = HOCRBorrowed::new_from_document(&doc).unwrap();
+        let owned = HOCR::from_hocr_borrowed(borrowed.clone());
+
+        assert_eq!(word_count(&borrowed), 510);
+        assert_eq!(word_count(&owned), 510);
+        assert_eq!(HocrDocument::plain_text(&borrowed), owned.plain_text());
+        assert_eq!(HocrDocument::summary(&borrowed), owned.summary());
+        assert_eq!(HocrDocument::validate(&borrowed), owned.validate());
+
+        let page = &borrowed.elements[0];
+        assert_eq!(HocrElement::bbox(page), owned.elements[0].bbox());
+        assert_eq!(
+            page.descendants().count(),
+            owned.elements[0].descendants().count()
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use crate::{geometry::BBox, spec_definitions::properties, traits::HocrElement};
+
+/// Points per inch, used to convert pixel sizes with the `scan_res` property.
+pub(crate) const POINTS_PER_INCH: f64 = 72.0;
+
+/// Millimetres per inch.
+const MILLIMETRES_PER_INCH: f64 = 25.4;
+
+/// The `scan_res` property of an element as horizontal and vertical resolution. A single value
+/// is used for both.
+pub(crate) fn scan_res<E: HocrElement>(e: &E) -> Option<(f64, f64)> {
+    match e.property(properties::SCAN_RES)? {
+        [x, y] => Some((x.as_ref().parse().ok()?, y.as_ref().parse().ok()?)),
+        [xy] => xy.as_ref().parse().ok().map(|r| (r, r)),
+        _ => None,
+    }
+}
+
+/// A unit of length on the page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Unit {
+    /// Image pixels, as used by all hOCR coordinates.
+    #[default]
+    Pixel,
+    /// Typographic points, 1/72 inch, as used by `x_fsize`.
+    Point,
+    Inch,
+    Millimetre,
+}
+
+impl Unit {
+    /// The abbreviation of the unit, e.g. `mm`.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Unit::Pixel => "px",
+            Unit::Point => "pt",
+            Unit::Inch => "in",
+            Unit::Millimetre => "mm",
+        }
+    }
+
+    fn per_inch(&self, dpi: f64) -> f64 {
+        match self {
+            Unit::Pixel => dpi,
+            Unit::Point => POINTS_PER_INCH,
+            Unit::Inch => 1.0,
+            Unit::Millimetre => MILLIMETRES_PER_INCH,
+        }
+    }
+}
+
+/// Converts lengths on a page between pixels and physical units, using the page's scan
+/// resolution.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Units {
+    x_dpi: f64,
+    y_dpi: f64,
+}
+
+impl Default for Units {
+    fn default() -> Self {
+        Self {
+            x_dpi: Self::DEFAULT_DPI,
+            y_dpi: Self::DEFAULT_DPI,
+        }
+    }
+}
+
+impl Units {
+    /// Resolution assumed for pages without a `scan_res`, as in Tesseract.
+    pub const DEFAULT_DPI: f64 = 300.0;
+
+    /// Create a new [`Units`] from the horizontal and vertical resolution in dots per inch.
+    /// Returns `None` unless both are positive and finite.
+    pub fn new(x_dpi: f64, y_dpi: f64) -> Option<Self> {
+        let valid = |dpi: f64| dpi > 0.0 && dpi.is_finite();
+        (valid(x_dpi) && valid(y_dpi)).then_some(Self { x_dpi, y_dpi })
+    }
+
+    /// Create a new [`Units`] with the same resolution in both directions. Returns `None`
+    /// unless it is positive and finite.
+    pub fn with_dpi(dpi: f64) -> Option<Self> {
+        Self::new(dpi, dpi)
+    }
+
+    /// Horizontal resolution in dots per inch.
+    pub fn x_dpi(&self) -> f64 {
+        self.x_dpi
+    }
+
+    /// Vertical resolution in dots per inch.
+    pub fn y_dpi(&self) -> f64 {
+        self.y_dpi
+    }
+
+    /// The units of a page from its `scan_res` property, or `default` if it has none or an
+    /// invalid resolution.
+    pub fn of_page<E: HocrElement>(page: &E, default: Units) -> Self {
+        scan_res(page)
+            .and_then(|(x, y)| Self::new(x, y))
+            .unwrap_or(default)
+    }
+
+    fn convert_with(value: f64, dpi: f64, from: Unit, to: Unit) -> f64 {
+        value / from.per_inch(dpi) * to.per_inch(dpi)
+    }
+
+    /// Convert a horizontal length or x coordinate.
+    pub fn convert_x(&self, value: f64, from: Unit, to: Unit) -> f64 {
+        Self::convert_with(value, self.x_dpi, from, to)
+    }
+
+    /// Convert a vertical length or y coordinate.
+    pub fn convert_y(&self, value: f64, from: Unit, to: Unit) -> f64 {
+        Self::convert_with(value, self.y_dpi, from, to)
+    }
+
+    /// Convert a distance in any direction, using the mean of the horizontal and vertical
+    /// resolution.
+    pub fn convert(&self, value: f64, from: Unit, to: Unit) -> f64 {
+        Self::convert_with(value, (self.x_dpi + self.y_dpi) / 2.0, from, to)
+    }
+
+    /// The coordinates `[x0, y0, x1, y1]` of a box in `to`.
+    pub fn convert_bbox(&self, b: &BBox, to: Unit) -> [f64; 4] {
+        let x = |v: i32| self.convert_x(v as f64, Unit::Pixel, to);
+        let y = |v: i32| self.convert_y(v as f64, Unit::Pixel, to);
+        [x(b.x0), y(b.y0), x(b.x1), y(b.y1)]
+    }
+
+    /// Convert a font size in points, as given by `x_fsize`, along the vertical axis.
+    pub fn font_size(&self, size: f64, to: Unit) -> f64 {
+        self.convert_y(size, Unit::Point, to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::document;
+    use crate::HOCR;
+
+    #[test]
+    fn convert_lengths() {
+        let units = Units::new(300.0, 150.0).unwrap();
+        assert_eq!(units.convert_x(300.0, Unit::Pixel, Unit::Inch), 1.0);
+        assert_eq!(units.convert_y(300.0, Unit::Pixel, Unit::Point), 144.0);
+        assert_eq!(units.convert(25.4, Unit::Millimetre, Unit::Pixel), 225.0);
+        assert_eq!(
+            units.convert_bbox(&BBox::new(0, 0, 600, 300), Unit::Inch),
+            [0.0, 0.0, 2.0, 2.0]
+        );
+        assert_eq!(units.font_size(12.0, Unit::Pixel), 25.0);
+    }
+
+    #[test]
+    fn page_resolution() {
+        let xml = document(
+            "ocr_page",
+            r#"<div class="ocr_page" id="page_1" title="bbox 0 0 100 100; scan_res 144 144"></div>
+            <div class="ocr_page" id="page_2" title="bbox 0 0 100 100"></div>"#,
+        );
+        let hocr = HOCR::from_str(&xml).unwrap();
+        let default = Units::with_dpi(72.0).unwrap();
+        assert_eq!(Units::of_page(&hocr.elements[0], default).x_dpi(), 144.0);
+        assert_eq!(Units::of_page(&hocr.elements[1], default), default);
+    }
+
+    #[test]
+    fn reject_invalid_resolution() {
+        assert_eq!(Units::with_dpi(0.0), None);
+        assert_eq!(Units::new(300.0, -1.0), None);
+        assert_eq!(Units::new(f64::NAN, 300.0), None);
+        assert_eq!(Units::with_dpi(f64::INFINITY), None);
+    }
+}
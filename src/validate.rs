@@ -0,0 +1,170 @@
+use crate::{
+    geometry::BBox,
+    glyph::parse_cuts,
+    hocr::HOCR,
+    spec_definitions::{elements::OCR_PAGE, properties},
+    traits::{HocrDocument, HocrElement},
+};
+
+/// A problem found by [`HOCR::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ValidationIssue {
+    /// Class of the offending element, or `None` for document-level issues.
+    pub element_type: Option<String>,
+    pub id: Option<String>,
+    pub property: Option<String>,
+    pub message: String,
+}
+
+impl std::fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.element_type, &self.id) {
+            (Some(t), Some(id)) => write!(f, "{t} '{id}'")?,
+            (Some(t), None) => write!(f, "{t}")?,
+            _ => write!(f, "document")?,
+        }
+        if let Some(property) = &self.property {
+            write!(f, ", property {property}")?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+fn numbers<S: AsRef<str>>(values: &[S]) -> Option<Vec<f64>> {
+    values.iter().map(|v| v.as_ref().parse().ok()).collect()
+}
+
+fn check_arity<S: AsRef<str>>(values: &[S], arity: usize) -> Option<String> {
+    match numbers(values) {
+        Some(n) if n.len() == arity => None,
+        _ => Some(format!("expected {arity} numeric value(s)")),
+    }
+}
+
+fn check_property<S: AsRef<str>>(name: &str, values: &[S], bbox: Option<BBox>) -> Option<String> {
+    match name {
+        properties::BBOX => match BBox::from_values(values) {
+            None => Some("expected 4 integer values".to_string()),
+            Some(b) if b.x0 > b.x1 || b.y0 > b.y1 => Some(format!("inverted coordinates {b}")),
+            _ => None,
+        },
+        properties::X_WCONF => match numbers(values).as_deref() {
+            Some([c]) if (0.0..=100.0).contains(c) => None,
+            Some([c]) => Some(format!("confidence {c} out of range 0-100")),
+            _ => Some("expected a single numeric value".to_string()),
+        },
+        properties::X_CONFS => numbers(values)
+            .is_none()
+            .then(|| "expected numeric values".to_string()),
+        properties::X_BBOXES => BBox::list_from_values(values)
+            .is_none()
+            .then(|| "expected groups of 4 integer values".to_string()),
+        properties::CUTS => match bbox {
+            None => Some("cuts require a bbox".to_string()),
+            Some(b) => parse_cuts(values, b)
+                .is_none()
+                .then(|| "malformed cut values".to_string()),
+        },
+        properties::PPAGENO => match values {
+            [v] if v.as_ref().parse::<u32>().is_ok() => None,
+            _ => Some("expected a single non-negative integer".to_string()),
+        },
+        properties::SCAN_RES | properties::BASELINE => check_arity(values, 2),
+        properties::TEXTANGLE | properties::X_FSIZE => check_arity(values, 1),
+        properties::IMAGE | properties::IMAGEMD5 | properties::X_FONT if values.len() != 1 => {
+            Some("expected a single value".to_string())
+        }
+        _ => None,
+    }
+}
+
+fn validate_element<E: HocrElement>(e: &E, issues: &mut Vec<ValidationIssue>) {
+    let bbox = e.bbox();
+    for (name, values) in e.properties() {
+        if let Some(message) = check_property(name, values, bbox) {
+            issues.push(ValidationIssue {
+                element_type: Some(e.element_type().to_string()),
+                id: e.id().map(str::to_string),
+                property: Some(name.to_string()),
+                message,
+            });
+        }
+    }
+
+    for child in e.children() {
+        validate_element(child, issues);
+    }
+}
+
+pub(crate) fn validate_document<D: HocrDocument + ?Sized>(hocr: &D) -> Vec<ValidationIssue> {
+    let mut issues = vec![];
+    for e in hocr.elements() {
+        validate_element(e, &mut issues);
+    }
+
+    if let Some(n) = hocr.number_of_pages() {
+        let pages = hocr
+            .iter()
+            .filter(|e| e.element_type() == OCR_PAGE)
+            .count();
+        if pages != n as usize {
+            issues.push(ValidationIssue {
+                element_type: None,
+                id: None,
+                property: None,
+                message: format!("ocr-number-of-pages is {n} but the document has {pages} page(s)"),
+            });
+        }
+    }
+
+    issues
+}
+
+impl HOCR {
+    /// Check property values and document metadata beyond what parsing enforces.
+    ///
+    /// Returns an empty vector if no problems were found.
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        HocrDocument::validate(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::document_with_meta;
+
+    #[test]
+    fn validate_property_values() {
+        let xml = document_with_meta(
+            "ocr_page ocrx_word",
+            r#"<meta name="ocr-number-of-pages" content="2"/>"#,
+            r#"<div class="ocr_page" id="page_1" title="bbox 0 0 100 100; ppageno 0">
+                <span class="ocrx_word" id="w1" title="bbox 10 10 5 20; x_wconf 101">a</span>
+                <span class="ocrx_word" id="w2" title="bbox 0 0 10 10; x_bboxes 0 0 10">b</span>
+            </div>"#,
+        );
+        let issues = HOCR::from_str(&xml).unwrap().validate();
+        let found: Vec<_> = issues
+            .iter()
+            .map(|i| (i.id.as_deref(), i.property.as_deref()))
+            .collect();
+
+        assert_eq!(
+            found,
+            vec![
+                (Some("w1"), Some("bbox")),
+                (Some("w1"), Some("x_wconf")),
+                (Some("w2"), Some("x_bboxes")),
+                (None, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_example() {
+        let xml = std::fs::read_to_string("examples/data/paper-image.hocr").unwrap();
+        assert!(HOCR::from_str(&xml).unwrap().validate().is_empty());
+    }
+}
@@ -0,0 +1,236 @@
+use roxmltree::{Document, Node};
+
+use crate::{
+    element::Element,
+    error::Result,
+    geometry::BBox,
+    parsing::{Properties, Values},
+    spec_definitions::properties,
+    HOCRParserError,
+};
+
+/// A lazy, non-allocating view of an hOCR element wrapping a [`roxmltree::Node`].
+///
+/// Unlike [`ElementBorrowed`](crate::ElementBorrowed), nothing is parsed up front: the
+/// `class` and `title` attributes are read when accessed, and children are visited on demand.
+/// Element and property names are not checked; use [`ElementView::to_element`] to convert
+/// to a checked [`Element`].
+#[derive(Debug, Clone, Copy)]
+pub struct ElementView<'a, 'input> {
+    node: Node<'a, 'input>,
+}
+
+impl<'a, 'input> ElementView<'a, 'input> {
+    /// Create a new [`ElementView`] of an XML element. Returns `None` for other node types.
+    pub fn new(node: Node<'a, 'input>) -> Option<Self> {
+        node.is_element().then_some(Self { node })
+    }
+
+    /// The underlying XML node.
+    pub fn node(&self) -> Node<'a, 'input> {
+        self.node
+    }
+
+    /// The hOCR element class, e.g. `ocr_line`.
+    pub fn element_type(&self) -> &'a str {
+        self.node.attribute("class").unwrap_or("")
+    }
+
+    /// The `id` attribute, e.g. `word_1_1`.
+    pub fn id(&self) -> Option<&'a str> {
+        self.node.attribute("id")
+    }
+
+    /// The XHTML tag name, e.g. `span`.
+    pub fn tag_name(&self) -> &'a str {
+        self.node.tag_name().name()
+    }
+
+    /// The `lang` attribute, e.g. `eng`.
+    pub fn lang(&self) -> Option<&'a str> {
+        self.node.attribute("lang")
+    }
+
+    /// The raw `title` attribute holding the properties.
+    pub fn title(&self) -> &'a str {
+        self.node.attribute("title").unwrap_or("")
+    }
+
+    /// Returns an iterator over the properties, parsed from the `title` attribute as it goes.
+    pub fn properties(&self) -> Properties<'a> {
+        Properties::new(self.title())
+    }
+
+    /// Returns an iterator over the values of the property with the given name, if present.
+    pub fn property(&self, name: &str) -> Option<Values<'a>> {
+        self.properties()
+            .find(|(n, _)| *n == name)
+            .map(|(_, values)| values)
+    }
+
+    /// Returns the parsed `bbox` property, if present and well-formed.
+    pub fn bbox(&self) -> Option<BBox> {
+        let mut values = self.property(properties::BBOX)?;
+        let mut next = || values.next()?.parse().ok();
+        let bbox = BBox::new(next()?, next()?, next()?, next()?);
+        values.next().is_none().then_some(bbox)
+    }
+
+    /// Returns the parsed `x_wconf` property, if present and well-formed.
+    pub fn confidence(&self) -> Option<f64> {
+        self.property(properties::X_WCONF)?.next()?.parse().ok()
+    }
+
+    /// The text before the first child element, unless it is whitespace only.
+    pub fn text(&self) -> Option<&'a str> {
+        self.node
+            .text()
+            .filter(|text| !text.chars().all(char::is_whitespace))
+    }
+
+    /// The text following the element, before its next sibling, unless it is whitespace only.
+    pub fn tail(&self) -> Option<&'a str> {
+        self.node
+            .tail()
+            .filter(|text| !text.chars().all(char::is_whitespace))
+    }
+
+    /// Returns an iterator over the child elements.
+    pub fn children(&self) -> impl Iterator<Item = ElementView<'a, 'input>> {
+        self.node.children().filter_map(ElementView::new)
+    }
+
+    /// Returns an iterator over this element and all of its descendants, in document order.
+    pub fn descendants(&self) -> impl Iterator<Item = ElementView<'a, 'input>> {
+        self.node.descendants().filter_map(ElementView::new)
+    }
+
+    /// Convert to an owned [`Element`], checking element and property names.
+    pub fn to_element(&self) -> Result<Element> {
+        Element::from_node(self.node)
+    }
+}
+
+/// A lazy view of a hOCR document, see [`ElementView`].
+#[derive(Debug, Clone, Copy)]
+pub struct HOCRView<'a, 'input> {
+    head: Node<'a, 'input>,
+    body: Node<'a, 'input>,
+}
+
+impl<'a, 'input> HOCRView<'a, 'input> {
+    /// Create a new [`HOCRView`] of a [`roxmltree::Document`].
+    ///
+    /// Only the presence of the `<head>` and `<body>` elements and the required
+    /// `ocr-system` and `ocr-capabilities` metadata is checked.
+    pub fn new(document: &'a Document<'input>) -> Result<Self> {
+        let child = |name| {
+            document
+                .root_element()
+                .children()
+                .find(|e| e.tag_name().name() == name)
+        };
+        let head = child("head").ok_or(HOCRParserError::NoHeadElement)?;
+        let body = child("body").ok_or(HOCRParserError::NoBodyElement)?;
+
+        let view = Self { head, body };
+        view.meta("ocr-system")
+            .ok_or(HOCRParserError::NoOCRSystem)?;
+        view.meta("ocr-capabilities")
+            .ok_or(HOCRParserError::NoOCRCapabilities)?;
+        Ok(view)
+    }
+
+    /// Returns the content of the first `<meta>` tag with the given name and a `content`
+    /// attribute, if present.
+    pub fn meta(&self, name: &str) -> Option<&'a str> {
+        self.head.children().find_map(|e| {
+            (e.tag_name().name() == "meta" && e.attribute("name") == Some(name))
+                .then(|| e.attribute("content"))
+                .flatten()
+        })
+    }
+
+    /// Content of the `ocr-system` metadata.
+    pub fn system(&self) -> &'a str {
+        self.meta("ocr-system").unwrap_or("")
+    }
+
+    /// Returns an iterator over the `ocr-capabilities` metadata.
+    pub fn capabilities(&self) -> impl Iterator<Item = &'a str> {
+        self.meta("ocr-capabilities")
+            .unwrap_or("")
+            .split_whitespace()
+    }
+
+    /// Returns an iterator over the top-level elements of the body.
+    pub fn elements(&self) -> impl Iterator<Item = ElementView<'a, 'input>> {
+        self.body.children().filter_map(ElementView::new)
+    }
+
+    /// Returns an iterator over all elements of the body, in document order.
+    pub fn iter(&self) -> impl Iterator<Item = ElementView<'a, 'input>> {
+        self.body.descendants().skip(1).filter_map(ElementView::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HOCRBorrowed, HOCR};
+
+    #[test]
+    fn view_matches_borrowed() {
+        let xml = std::fs::read_to_string("examples/data/paper-image.hocr").unwrap();
+        let options = roxmltree::ParsingOptions {
+            allow_dtd: true,
+            ..Default::default()
+        };
+        let doc = Document::parse_with_options(&xml, options).unwrap();
+        let view = HOCRView::new(&doc).unwrap();
+        let hocr = HOCR::from_hocr_borrowed(HOCRBorrowed::new_from_document(&doc).unwrap());
+
+        assert_eq!(view.system(), hocr.system);
+        assert!(view
+            .capabilities()
+            .eq(hocr.capabilities.iter().map(String::as_str)));
+
+        let elements: Vec<_> = view.iter().collect();
+        let owned: Vec<_> = hocr.iter_document_order().collect();
+        assert_eq!(elements.len(), owned.len());
+        for (v, e) in elements.iter().zip(owned) {
+            assert_eq!(v.element_type(), e.element_type);
+            assert_eq!(v.id(), e.id.as_deref());
+            assert_eq!(v.text(), e.text.as_deref());
+            assert_eq!(v.bbox(), e.bbox());
+            assert_eq!(v.confidence(), e.confidence());
+            let properties: Vec<(&str, Vec<&str>)> =
+                v.properties().map(|(n, vs)| (n, vs.collect())).collect();
+            assert_eq!(properties.len(), e.properties.len());
+            for ((n, vs), (en, evs)) in properties.iter().zip(&e.properties) {
+                assert_eq!(n, en);
+                assert_eq!(vs, evs);
+            }
+        }
+
+        assert_eq!(
+            view.elements().next().unwrap().to_element().unwrap(),
+            hocr.elements[0]
+        );
+    }
+
+    #[test]
+    fn quoted_property_values() {
+        let doc = Document::parse(
+            r#"<span class="ocr_page" title='image "/tmp/a b.png"; bbox 0 0 10 20 30'/>"#,
+        )
+        .unwrap();
+        let view = ElementView::new(doc.root_element()).unwrap();
+
+        assert_eq!(
+            view.property("image").unwrap().collect::<Vec<_>>(),
+            ["/tmp/a b.png"]
+        );
+        assert_eq!(view.bbox(), None);
+    }
+}
@@ -0,0 +1,241 @@
+use std::fmt::Write;
+
+use crate::{
+    element::Element,
+    hocr::HOCR,
+    parsing::allows_formatting,
+    spec_definitions::{
+        elements::{OCRX_WORD, OCR_PAR},
+        properties,
+    },
+    summary::LINE_ELEMENTS,
+};
+
+const XHTML_PROLOGUE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE html PUBLIC "-//W3C//DTD XHTML 1.0 Transitional//EN"
+    "http://www.w3.org/TR/xhtml1/DTD/xhtml1-transitional.dtd">
+"#;
+
+/// Properties whose values are quoted even without whitespace, following Tesseract's output.
+const QUOTED_PROPERTIES: [&str; 2] = [properties::IMAGE, properties::X_FONT];
+
+pub(crate) fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Format properties as the value of a `title` attribute.
+pub(crate) fn format_properties<N: AsRef<str>, V: AsRef<str>>(
+    properties: &[(N, Vec<V>)],
+) -> String {
+    properties
+        .iter()
+        .map(|(name, values)| {
+            let name = name.as_ref();
+            let mut property = name.to_string();
+            for value in values {
+                let value = value.as_ref();
+                if QUOTED_PROPERTIES.contains(&name) || value.contains(char::is_whitespace) {
+                    let _ = write!(property, " \"{value}\"");
+                } else {
+                    let _ = write!(property, " {value}");
+                }
+            }
+            property
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+// Constructed elements may lack a tag name, so infer the one Tesseract would use.
+fn tag_name(e: &Element) -> &str {
+    if !e.tag_name.is_empty() {
+        return &e.tag_name;
+    }
+
+    let is_inline = |e: &Element| e.element_type == OCRX_WORD || e.children.is_empty();
+
+    if e.element_type == OCR_PAR {
+        "p"
+    } else if e.element_type == OCRX_WORD
+        || (LINE_ELEMENTS.contains(&e.element_type.as_str()) && e.children.iter().all(is_inline))
+    {
+        "span"
+    } else {
+        "div"
+    }
+}
+
+fn write_element(out: &mut String, e: &Element, depth: usize) {
+    out.push_str(&" ".repeat(depth));
+    write_tag(out, e, depth);
+    out.push('\n');
+}
+
+/// Write `children` one per line at `depth`, or all on the current line if `inline`.
+fn write_children(out: &mut String, children: &[Element], depth: usize, inline: bool) {
+    if inline {
+        for child in children {
+            write_tag(out, child, depth);
+        }
+    } else if !children.is_empty() {
+        out.push('\n');
+        for child in children {
+            write_element(out, child, depth);
+        }
+        out.push_str(&" ".repeat(depth - 1));
+    }
+}
+
+/// Whether any of `children` is followed by text, which whitespace between them would change.
+fn has_tails(children: &[Element]) -> bool {
+    children.iter().any(|c| c.tail.is_some())
+}
+
+/// Write `e` and its tail without a trailing newline. The children of words, formatting
+/// elements and elements with mixed content are written inline, as any whitespace between them
+/// would become part of the text.
+fn write_tag(out: &mut String, e: &Element, depth: usize) {
+    let tag = tag_name(e);
+
+    let _ = write!(out, "<{tag}");
+    // inline formatting elements such as `<strong>` have no class
+    if !e.element_type.is_empty() {
+        let _ = write!(out, " class=\"{}\"", escape(&e.element_type));
+    }
+    if let Some(id) = &e.id {
+        let _ = write!(out, " id=\"{}\"", escape(id));
+    }
+    if let Some(lang) = &e.lang {
+        let _ = write!(out, " lang=\"{}\"", escape(lang));
+    }
+    for (name, value) in &e.attributes {
+        let _ = write!(out, " {name}=\"{}\"", escape(value));
+    }
+    if !e.properties.is_empty() {
+        let _ = write!(
+            out,
+            " title=\"{}\"",
+            escape(&format_properties(&e.properties))
+        );
+    }
+    out.push('>');
+
+    if let Some(text) = &e.text {
+        out.push_str(&escape(text));
+    }
+
+    let inline =
+        allows_formatting(&e.element_type, tag) || e.text.is_some() || has_tails(&e.children);
+    write_children(out, &e.children, depth + 1, inline);
+
+    let _ = write!(out, "</{tag}>");
+    if let Some(tail) = &e.tail {
+        out.push_str(&escape(tail));
+    }
+}
+
+fn write_meta(out: &mut String, name: &str, content: &str) {
+    let _ = writeln!(
+        out,
+        "  <meta name=\"{}\" content=\"{}\"/>",
+        escape(name),
+        escape(content)
+    );
+}
+
+impl HOCR {
+    /// Serialize the document as hOCR XHTML.
+    pub fn to_xhtml(&self) -> String {
+        let mut out = String::from(XHTML_PROLOGUE);
+        out.push_str("<html xmlns=\"http://www.w3.org/1999/xhtml\">\n <head>\n  <title></title>\n");
+        out.push_str("  <meta http-equiv=\"Content-Type\" content=\"text/html;charset=utf-8\"/>\n");
+
+        write_meta(&mut out, "ocr-system", &self.system);
+        write_meta(&mut out, "ocr-capabilities", &self.capabilities.join(" "));
+        if let Some(n) = self.number_of_pages {
+            write_meta(&mut out, "ocr-number-of-pages", &n.to_string());
+        }
+        if let Some(langs) = &self.langs {
+            write_meta(&mut out, "ocr-langs", &langs.join(" "));
+        }
+        if let Some(scripts) = &self.scripts {
+            write_meta(&mut out, "ocr-scripts", &scripts.join(" "));
+        }
+        for (name, content) in &self.metadata {
+            write_meta(&mut out, name, content);
+        }
+
+        out.push_str(" </head>\n <body>");
+        write_children(&mut out, &self.elements, 2, has_tails(&self.elements));
+        out.push_str("</body>\n</html>\n");
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::document;
+
+    #[test]
+    fn format_quoted_properties() {
+        let properties = vec![
+            ("image", vec!["page 1.png"]),
+            ("bbox", vec!["0", "0", "10", "20"]),
+            ("x_font", vec!["Times"]),
+        ];
+        assert_eq!(
+            format_properties(&properties),
+            r#"image "page 1.png"; bbox 0 0 10 20; x_font "Times""#
+        );
+    }
+
+    #[test]
+    fn write_and_reparse_example() {
+        let xml = std::fs::read_to_string("examples/data/paper-image.hocr").unwrap();
+        let hocr = HOCR::from_str(&xml).unwrap();
+        let written = HOCR::from_str(&hocr.to_xhtml()).unwrap();
+
+        assert_eq!(written.system, hocr.system);
+        assert_eq!(written.capabilities, hocr.capabilities);
+        assert_eq!(written.plain_text(), hocr.plain_text());
+        assert_eq!(written.iter().count(), hocr.iter().count());
+
+        let ids = |h: &HOCR| h.iter().map(|e| e.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&written), ids(&hocr));
+    }
+
+    #[test]
+    fn write_formatted_word_inline() {
+        let xml = document(
+            "ocr_page ocrx_word",
+            r#"<div class="ocr_page" id="page_1" title="bbox 0 0 100 100">
+                <span class="ocrx_word" id="w1" title="bbox 0 0 50 10">Hel<em>lo</em>!</span>
+            </div>"#,
+        );
+        let hocr = HOCR::from_str(&xml).unwrap();
+        assert_eq!(hocr.plain_text(), "Hello!");
+
+        let xhtml = hocr.to_xhtml();
+        assert!(xhtml.contains(r#"title="bbox 0 0 50 10">Hel<em>lo</em>!</span>"#));
+        let written = HOCR::from_str(&xhtml).unwrap();
+        assert_eq!(written.plain_text(), "Hello!");
+        assert_eq!(written.elements, hocr.elements);
+
+        // the `<em>` is part of the word, not an element of its own
+        assert_eq!(written.iter().count(), 2);
+        assert!(!written.summary().element_counts.contains_key(""));
+    }
+}